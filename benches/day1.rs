@@ -0,0 +1,71 @@
+use aoc_rust_2024::day1::part1;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// The original part 1, which collects every line into a `Vec` and uses
+// comparison sorts.
+fn part1_baseline(input: &str) -> i64 {
+    let mut left_list: Vec<i64> = Vec::new();
+    let mut right_list: Vec<i64> = Vec::new();
+
+    for line in input.split('\n') {
+        if !line.is_empty() {
+            let line_numbers: Vec<i64> = line
+                .split("   ")
+                .map(|number| number.parse::<i64>().unwrap())
+                .collect();
+
+            left_list.push(line_numbers[0]);
+            right_list.push(line_numbers[1]);
+        }
+    }
+
+    left_list.sort();
+    right_list.sort();
+
+    left_list
+        .iter()
+        .zip(right_list.iter())
+        .map(|(left, right)| (left - right).abs())
+        .sum()
+}
+
+fn location_lists(lines: usize, id_range: u64) -> String {
+    let mut seed: u64 = 0x2024_1201;
+    let mut next_id = move || {
+        seed ^= seed << 13;
+        seed ^= seed >> 7;
+        seed ^= seed << 17;
+        10_000 + seed % id_range
+    };
+
+    (0..lines)
+        .map(|_| format!("{}   {}\n", next_id(), next_id()))
+        .collect()
+}
+
+fn total_distance(c: &mut Criterion) {
+    let mut group = c.benchmark_group("day1 part 1");
+
+    group.sample_size(20);
+
+    for (lines, id_range) in [(1_000_000, 90_000), (1_000_000, 1_000_000_000)] {
+        let input = location_lists(lines, id_range);
+        let parameter = format!("{} lines, range {}", lines, id_range);
+
+        group.bench_with_input(
+            BenchmarkId::new("baseline", &parameter),
+            &input,
+            |b, input| b.iter(|| part1_baseline(black_box(input))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("current", &parameter),
+            &input,
+            |b, input| b.iter(|| part1(black_box(input))),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, total_distance);
+criterion_main!(benches);
@@ -0,0 +1,51 @@
+use aoc_rust_2024::day2::{check_report, diagnose_report, SafetyRules};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// The original Problem Dampener check, which copies the report for every level
+// it tries to remove.
+fn single_removal_baseline(report: &[i64]) -> bool {
+    check_report(report).is_ok()
+        || (0..report.len()).any(|index| {
+            let mut report_with_removed_level = report.to_vec();
+            report_with_removed_level.remove(index);
+
+            check_report(&report_with_removed_level).is_ok()
+        })
+}
+
+// Increasing report with a single out-of-place level in the middle.
+fn report_with_bad_level(length: usize) -> Vec<i64> {
+    let mut report: Vec<i64> = (0..length as i64).map(|level| level * 2).collect();
+    report[length / 2] = 0;
+    report
+}
+
+fn single_removal(c: &mut Criterion) {
+    let rules = SafetyRules::default();
+    let mut group = c.benchmark_group("day2 single removal");
+
+    for length in [1_000, 5_000] {
+        let report = report_with_bad_level(length);
+
+        group.bench_with_input(
+            BenchmarkId::new("baseline", length),
+            &report,
+            |b, report| b.iter(|| single_removal_baseline(black_box(report))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("single pass", length),
+            &report,
+            |b, report| b.iter(|| rules.is_safe_with_single_removal(black_box(report))),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("diagnose", length),
+            &report,
+            |b, report| b.iter(|| diagnose_report(black_box(report), true).is_safe()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, single_removal);
+criterion_main!(benches);
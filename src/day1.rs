@@ -68,68 +68,577 @@ So, for these example lists, the similarity score at the end of this process is
 Once again consider your left and right lists. What is their similarity score?
 */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-pub fn part1(input: &str) -> Result<String, String> {
-    let mut left_list: Vec<i64> = Vec::new();
-    let mut right_list: Vec<i64> = Vec::new();
-    let mut sum_of_distances: i64 = 0;
-
-    for line in input.split('\n') {
-        if !line.is_empty() {
-            let line_numbers: Vec<i64> = line
-                .split("   ")
-                .map(|number| match number.parse::<i64>() {
-                    Ok(value) => value,
-                    Err(_) => panic!("Unable to parse list number: {:?}", number),
-                })
-                .collect();
+// Splits each non-empty line on any whitespace, or on `delimiter` when given,
+// and returns one list per column. Every line must have as many columns as the
+// first one.
+pub fn parse_lists(input: &str, delimiter: Option<&str>) -> Result<Vec<Vec<i64>>, String> {
+    let mut lists: Vec<Vec<i64>> = Vec::new();
 
-            left_list.push(line_numbers[0]);
-            right_list.push(line_numbers[1]);
+    for (line_index, line) in input.split('\n').enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let line_number = line_index + 1;
+        let fields: Vec<&str> = match delimiter {
+            Some(delimiter) => line.split(delimiter).map(|field| field.trim()).collect(),
+            None => line.split_whitespace().collect(),
+        };
+
+        if lists.is_empty() {
+            lists = vec![Vec::new(); fields.len()];
+        } else if fields.len() != lists.len() {
+            return Err(format!(
+                "Line {}: expected {} columns but found {}",
+                line_number,
+                lists.len(),
+                fields.len()
+            ));
+        }
+
+        for (list, field) in lists.iter_mut().zip(fields) {
+            match field.parse::<i64>() {
+                Ok(value) => list.push(value),
+                Err(_) => {
+                    return Err(format!(
+                        "Line {}: unable to parse location ID {:?}",
+                        line_number, field
+                    ))
+                }
+            }
         }
     }
 
-    left_list.sort();
-    right_list.sort();
+    Ok(lists)
+}
+
+fn parse_id(digits: &[u8]) -> Option<i64> {
+    let (sign, digits) = match digits.split_first() {
+        Some((b'-', digits)) => (-1, digits),
+        _ => (1, digits),
+    };
 
-    for (left, right) in left_list.iter().zip(right_list.iter()) {
-        sum_of_distances += (left - right).abs();
+    if digits.is_empty() {
+        return None;
     }
 
-    Ok(sum_of_distances.to_string())
+    digits.iter().try_fold(0_i64, |value, digit| match digit {
+        b'0'..=b'9' => value
+            .checked_mul(10)?
+            .checked_add(sign * (digit - b'0') as i64),
+        _ => None,
+    })
 }
 
-pub fn part2(input: &str) -> Result<String, String> {
-    let mut left_list: Vec<i64> = Vec::new();
+// Reads two whitespace-separated columns straight from the input bytes without
+// allocating anything per line. Anything unusual gives up and returns `None` so
+// that `parse_lists` can report it.
+fn parse_two_lists_fast(input: &str) -> Option<(Vec<i64>, Vec<i64>)> {
+    let lines = input.as_bytes().split(|&character| character == b'\n');
+    let mut left_list: Vec<i64> = Vec::with_capacity(input.len() / 8);
+    let mut right_list: Vec<i64> = Vec::with_capacity(input.len() / 8);
+
+    for line in lines {
+        let mut fields = line
+            .split(|character| character.is_ascii_whitespace())
+            .filter(|field| !field.is_empty());
+
+        match (fields.next(), fields.next(), fields.next()) {
+            (None, _, _) => continue,
+            (Some(left), Some(right), None) => {
+                left_list.push(parse_id(left)?);
+                right_list.push(parse_id(right)?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some((left_list, right_list))
+}
+
+fn parse_two_lists(input: &str) -> Result<(Vec<i64>, Vec<i64>), String> {
+    if let Some(lists) = parse_two_lists_fast(input) {
+        return Ok(lists);
+    }
+
+    let mut lists = parse_lists(input, None)?;
+
+    match lists.len() {
+        2 => {
+            let right_list = lists.remove(1);
+            let left_list = lists.remove(0);
+
+            Ok((left_list, right_list))
+        }
+        columns => Err(format!("Expected 2 lists but found {}", columns)),
+    }
+}
+
+const RADIX_SORT_MIN_LENGTH: usize = 1024;
+
+// Small lists use the standard sort. Larger ones use a counting sort when the IDs
+// span a range no wider than about twice the list, and a radix sort otherwise.
+pub fn sort_ids(list: &mut [i64]) {
+    if list.len() < RADIX_SORT_MIN_LENGTH {
+        list.sort_unstable();
+        return;
+    }
+
+    let minimum = *list.iter().min().unwrap_or(&0);
+    let maximum = *list.iter().max().unwrap_or(&0);
+    let range = (maximum as i128 - minimum as i128) as u64;
+
+    if range < 2 * list.len() as u64 {
+        counting_sort(list, minimum, range);
+    } else {
+        radix_sort(list, minimum, range);
+    }
+}
+
+fn counting_sort(list: &mut [i64], minimum: i64, range: u64) {
+    let mut counts: Vec<usize> = vec![0; range as usize + 1];
+
+    for id in list.iter() {
+        counts[id.wrapping_sub(minimum) as u64 as usize] += 1;
+    }
+
+    let mut index = 0;
+
+    for (offset, count) in counts.into_iter().enumerate() {
+        list[index..index + count].fill(minimum.wrapping_add(offset as i64));
+        index += count;
+    }
+}
+
+fn radix_sort(list: &mut [i64], minimum: i64, range: u64) {
+    let mut keys: Vec<u64> = list
+        .iter()
+        .map(|id| id.wrapping_sub(minimum) as u64)
+        .collect();
+    let mut sorted_keys: Vec<u64> = vec![0; keys.len()];
+    let passes = (u64::BITS - range.leading_zeros()).div_ceil(8);
+
+    for pass in 0..passes {
+        let shift = pass * 8;
+        let mut offsets = [0_usize; 257];
+
+        for key in keys.iter() {
+            offsets[((key >> shift) & 0xff) as usize + 1] += 1;
+        }
+
+        for digit in 0..256 {
+            offsets[digit + 1] += offsets[digit];
+        }
+
+        for key in keys.iter() {
+            let digit = ((key >> shift) & 0xff) as usize;
+            sorted_keys[offsets[digit]] = *key;
+            offsets[digit] += 1;
+        }
+
+        std::mem::swap(&mut keys, &mut sorted_keys);
+    }
+
+    for (id, key) in list.iter_mut().zip(keys) {
+        *id = minimum.wrapping_add(key as i64);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DistanceMetric {
+    Absolute,
+    Squared,
+    Maximum,
+    Mismatches,
+}
+
+impl DistanceMetric {
+    // Cost of a single pair, saturating at `u128::MAX`, so that any two IDs can
+    // be compared without overflowing.
+    fn pair_cost(&self, (left, right): (i64, i64)) -> u128 {
+        let difference = left.abs_diff(right) as u128;
+
+        match self {
+            DistanceMetric::Squared => difference.saturating_mul(difference),
+            DistanceMetric::Mismatches => (difference != 0) as u128,
+            DistanceMetric::Absolute | DistanceMetric::Maximum => difference,
+        }
+    }
+
+    pub fn measure(&self, pairs: &[(i64, i64)]) -> Result<i64, String> {
+        let mut costs = pairs.iter().map(|pair| self.pair_cost(*pair));
+        let total = match self {
+            DistanceMetric::Maximum => costs.max().unwrap_or(0),
+            _ => costs
+                .try_fold(0u128, |total, cost| total.checked_add(cost))
+                .unwrap_or(u128::MAX),
+        };
+
+        i64::try_from(total).map_err(|_| "Distance does not fit in 64 bits".to_string())
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Pairing {
+    ByRank,
+    ByPosition,
+    MinimumCost,
+}
+
+// Pairs the two lists up, leaving out the extra IDs of the longer list. For
+// mismatches, equal IDs are paired first.
+pub fn pair_lists(
+    left_list: &[i64],
+    right_list: &[i64],
+    pairing: Pairing,
+    metric: DistanceMetric,
+) -> Vec<(i64, i64)> {
+    match (pairing, metric) {
+        (Pairing::ByPosition, _) => left_list
+            .iter()
+            .copied()
+            .zip(right_list.iter().copied())
+            .collect(),
+        (Pairing::MinimumCost, DistanceMetric::Mismatches) => {
+            let mut counts: HashMap<i64, usize> = HashMap::new();
+            let mut pairs: Vec<(i64, i64)> = Vec::new();
+            let mut unmatched_left: Vec<i64> = Vec::new();
+
+            for number in right_list.iter() {
+                *counts.entry(*number).or_insert(0) += 1;
+            }
+
+            for number in left_list.iter() {
+                match counts.get_mut(number) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        pairs.push((*number, *number));
+                    }
+                    _ => unmatched_left.push(*number),
+                }
+            }
+
+            let unmatched_right = right_list
+                .iter()
+                .filter(|number| match counts.get_mut(number) {
+                    Some(count) if *count > 0 => {
+                        *count -= 1;
+                        true
+                    }
+                    _ => false,
+                });
+
+            pairs.extend(unmatched_left.into_iter().zip(unmatched_right.copied()));
+            pairs
+        }
+        (Pairing::MinimumCost, _) => minimum_cost_pairs(left_list, right_list, metric),
+        (Pairing::ByRank, _) => {
+            let mut left_list = left_list.to_vec();
+            let mut right_list = right_list.to_vec();
+
+            sort_ids(&mut left_list);
+            sort_ids(&mut right_list);
+
+            left_list.into_iter().zip(right_list).collect()
+        }
+    }
+}
+
+// For the absolute, squared and maximum metrics, some optimal pairing of the
+// sorted lists never crosses, so only the choice of which extra IDs of the
+// longer list to leave out is left. With lists of equal length, that is pairing
+// by rank.
+fn minimum_cost_pairs(
+    left_list: &[i64],
+    right_list: &[i64],
+    metric: DistanceMetric,
+) -> Vec<(i64, i64)> {
+    let mut left_list = left_list.to_vec();
+    let mut right_list = right_list.to_vec();
+
+    sort_ids(&mut left_list);
+    sort_ids(&mut right_list);
+
+    let swapped = left_list.len() > right_list.len();
+    let (shorter, longer) = match swapped {
+        true => (&right_list, &left_list),
+        false => (&left_list, &right_list),
+    };
+    let extra = longer.len() - shorter.len();
+    let add_pair = |cost: u128, pair: (i64, i64)| match metric {
+        DistanceMetric::Maximum => cost.max(metric.pair_cost(pair)),
+        _ => cost.saturating_add(metric.pair_cost(pair)),
+    };
+
+    // costs[i][k] is the lowest cost of pairing the first i IDs of the shorter
+    // list with the first i + k IDs of the longer one, leaving k of them out.
+    let mut costs: Vec<Vec<u128>> = vec![vec![0; extra + 1]; shorter.len() + 1];
+    let mut left_out: Vec<Vec<bool>> = vec![vec![false; extra + 1]; shorter.len() + 1];
+
+    for index in 1..=shorter.len() {
+        for skipped in 0..=extra {
+            let pair = (shorter[index - 1], longer[index + skipped - 1]);
+            costs[index][skipped] = add_pair(costs[index - 1][skipped], pair);
+
+            if skipped > 0 && costs[index][skipped - 1] < costs[index][skipped] {
+                costs[index][skipped] = costs[index][skipped - 1];
+                left_out[index][skipped] = true;
+            }
+        }
+    }
+
+    let mut pairs: Vec<(i64, i64)> = Vec::with_capacity(shorter.len());
+    let (mut index, mut skipped) = (shorter.len(), extra);
+
+    while index > 0 {
+        if left_out[index][skipped] {
+            skipped -= 1;
+        } else {
+            let (shorter_id, longer_id) = (shorter[index - 1], longer[index + skipped - 1]);
+
+            pairs.push(match swapped {
+                true => (longer_id, shorter_id),
+                false => (shorter_id, longer_id),
+            });
+            index -= 1;
+        }
+    }
+
+    pairs.reverse();
+    pairs
+}
+
+pub fn distance(
+    left_list: &[i64],
+    right_list: &[i64],
+    metric: DistanceMetric,
+    pairing: Pairing,
+) -> Result<i64, String> {
+    metric.measure(&pair_lists(left_list, right_list, pairing, metric))
+}
+
+pub fn total_distance(left_list: &[i64], right_list: &[i64]) -> Result<i64, String> {
+    distance(
+        left_list,
+        right_list,
+        DistanceMetric::Absolute,
+        Pairing::ByRank,
+    )
+}
+
+pub fn similarity_score(left_list: &[i64], right_list: &[i64]) -> i64 {
     let mut counts = HashMap::new();
     let mut similarity_score: i64 = 0;
 
-    for line in input.split('\n') {
-        if !line.is_empty() {
-            let line_numbers: Vec<i64> = line
-                .split("   ")
-                .map(|number| match number.parse::<i64>() {
-                    Ok(value) => value,
-                    Err(_) => panic!("Unable to parse list number: {:?}", number),
+    for number in right_list.iter() {
+        *counts.entry(*number).or_insert(0) += 1;
+    }
+
+    for number in left_list.iter() {
+        similarity_score += number * counts.get(number).unwrap_or(&0);
+    }
+
+    similarity_score
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SimilarityContribution {
+    pub id: i64,
+    pub left_count: usize,
+    pub right_count: usize,
+    pub contribution: i64,
+}
+
+// One entry per distinct ID of the left list, largest contribution first. The
+// contributions add up to the similarity score.
+pub fn similarity_breakdown(left_list: &[i64], right_list: &[i64]) -> Vec<SimilarityContribution> {
+    let mut left_counts: HashMap<i64, usize> = HashMap::new();
+    let mut right_counts: HashMap<i64, usize> = HashMap::new();
+
+    for number in left_list.iter() {
+        *left_counts.entry(*number).or_insert(0) += 1;
+    }
+
+    for number in right_list.iter() {
+        *right_counts.entry(*number).or_insert(0) += 1;
+    }
+
+    let mut breakdown: Vec<SimilarityContribution> = left_counts
+        .into_iter()
+        .map(|(id, left_count)| {
+            let right_count = *right_counts.get(&id).unwrap_or(&0);
+
+            SimilarityContribution {
+                id,
+                left_count,
+                right_count,
+                contribution: id * (left_count * right_count) as i64,
+            }
+        })
+        .collect();
+
+    breakdown.sort_by_key(|entry| (std::cmp::Reverse(entry.contribution), entry.id));
+
+    breakdown
+}
+
+pub fn similarity_breakdown_table(input: &str) -> Result<String, String> {
+    let (left_list, right_list) = parse_two_lists(input)?;
+    let mut table = format!(
+        "{:>12}  {:>10}  {:>11}  {:>12}\n",
+        "id", "left count", "right count", "contribution"
+    );
+
+    for entry in similarity_breakdown(&left_list, &right_list) {
+        table.push_str(&format!(
+            "{:>12}  {:>10}  {:>11}  {:>12}\n",
+            entry.id, entry.left_count, entry.right_count, entry.contribution
+        ));
+    }
+
+    Ok(table)
+}
+
+pub fn similarity_breakdown_csv(input: &str) -> Result<String, String> {
+    let (left_list, right_list) = parse_two_lists(input)?;
+    let mut csv = String::from("id,left_count,right_count,contribution\n");
+
+    for entry in similarity_breakdown(&left_list, &right_list) {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            entry.id, entry.left_count, entry.right_count, entry.contribution
+        ));
+    }
+
+    Ok(csv)
+}
+
+// Compares every pair of lists. `similarity_scores[i][j]` scores list i against
+// the counts of list j, so that matrix is not symmetric.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reconciliation {
+    pub distances: Vec<Vec<i64>>,
+    pub similarity_scores: Vec<Vec<i64>>,
+    pub unique_ids: Vec<Vec<i64>>,
+}
+
+impl Reconciliation {
+    pub fn new(lists: &[Vec<i64>]) -> Result<Self, String> {
+        let pairwise = |score: fn(&[i64], &[i64]) -> i64| -> Vec<Vec<i64>> {
+            lists
+                .iter()
+                .map(|left_list| {
+                    lists
+                        .iter()
+                        .map(|right_list| score(left_list, right_list))
+                        .collect()
                 })
-                .collect();
+                .collect()
+        };
+        let mut lists_containing: HashMap<i64, usize> = HashMap::new();
 
-            left_list.push(line_numbers[0]);
-            *counts.entry(line_numbers[1]).or_insert(0) += 1;
+        for list in lists.iter() {
+            let distinct_ids: HashSet<&i64> = list.iter().collect();
+
+            for id in distinct_ids {
+                *lists_containing.entry(*id).or_insert(0) += 1;
+            }
         }
+
+        let unique_ids = lists
+            .iter()
+            .map(|list| {
+                let mut unique_ids: Vec<i64> = list
+                    .iter()
+                    .filter(|id| lists_containing.get(id) == Some(&1))
+                    .copied()
+                    .collect();
+
+                unique_ids.sort();
+                unique_ids.dedup();
+                unique_ids
+            })
+            .collect();
+
+        let distances = lists
+            .iter()
+            .map(|left_list| {
+                lists
+                    .iter()
+                    .map(|right_list| total_distance(left_list, right_list))
+                    .collect::<Result<Vec<i64>, String>>()
+            })
+            .collect::<Result<Vec<Vec<i64>>, String>>()?;
+
+        Ok(Self {
+            distances,
+            similarity_scores: pairwise(similarity_score),
+            unique_ids,
+        })
     }
 
-    for number in left_list.iter() {
-        similarity_score += number * counts.get(number).unwrap_or(&0);
+    pub fn to_table(&self) -> String {
+        let mut table = String::new();
+
+        for (title, matrix) in [
+            ("Distances", &self.distances),
+            ("Similarity scores", &self.similarity_scores),
+        ] {
+            table.push_str(&format!("{}\n{:>6}", title, ""));
+
+            for column in 0..matrix.len() {
+                table.push_str(&format!("  {:>12}", format!("list {}", column + 1)));
+            }
+
+            table.push('\n');
+
+            for (row, values) in matrix.iter().enumerate() {
+                table.push_str(&format!("{:>6}", format!("list {}", row + 1)));
+
+                for value in values {
+                    table.push_str(&format!("  {:>12}", value));
+                }
+
+                table.push('\n');
+            }
+
+            table.push('\n');
+        }
+
+        table.push_str("Unique IDs\n");
+
+        for (list, unique_ids) in self.unique_ids.iter().enumerate() {
+            let unique_ids: Vec<String> = unique_ids.iter().map(|id| id.to_string()).collect();
+
+            table.push_str(&format!("list {}: {}\n", list + 1, unique_ids.join(" ")));
+        }
+
+        table
     }
+}
 
-    Ok(similarity_score.to_string())
+pub fn reconciliation_table(input: &str) -> Result<String, String> {
+    Ok(Reconciliation::new(&parse_lists(input, None)?)?.to_table())
+}
+
+pub fn part1(input: &str) -> Result<String, String> {
+    let (left_list, right_list) = parse_two_lists(input)?;
+
+    Ok(total_distance(&left_list, &right_list)?.to_string())
+}
+
+pub fn part2(input: &str) -> Result<String, String> {
+    let (left_list, right_list) = parse_two_lists(input)?;
+
+    Ok(similarity_score(&left_list, &right_list).to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day1::{part1, part2};
+    use super::*;
+    use crate::test_utils::Xorshift;
 
     static TEST_INPUT: &str = "\
 3   4
@@ -149,4 +658,235 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(31.to_string()));
     }
+
+    #[test]
+    fn test_distance_metrics_and_pairings() {
+        let (left_list, right_list) = parse_two_lists(TEST_INPUT).unwrap();
+        let distance_of =
+            |metric, pairing| distance(&left_list, &right_list, metric, pairing).unwrap();
+
+        assert_eq!(distance_of(DistanceMetric::Absolute, Pairing::ByRank), 11);
+        assert_eq!(distance_of(DistanceMetric::Squared, Pairing::ByRank), 35);
+        assert_eq!(distance_of(DistanceMetric::Maximum, Pairing::ByRank), 5);
+        assert_eq!(distance_of(DistanceMetric::Mismatches, Pairing::ByRank), 5);
+        assert_eq!(
+            distance_of(DistanceMetric::Absolute, Pairing::ByPosition),
+            13
+        );
+        assert_eq!(distance_of(DistanceMetric::Maximum, Pairing::ByPosition), 6);
+        assert_eq!(
+            distance_of(DistanceMetric::Absolute, Pairing::MinimumCost),
+            11
+        );
+        assert_eq!(
+            distance_of(DistanceMetric::Mismatches, Pairing::MinimumCost),
+            2
+        );
+        assert_eq!(
+            pair_lists(
+                &left_list,
+                &right_list,
+                Pairing::MinimumCost,
+                DistanceMetric::Mismatches
+            )
+            .len(),
+            6
+        );
+
+        assert_eq!(
+            distance(
+                &[10],
+                &[1, 10],
+                DistanceMetric::Absolute,
+                Pairing::MinimumCost
+            ),
+            Ok(0)
+        );
+        assert_eq!(
+            pair_lists(
+                &[1, 10],
+                &[10],
+                Pairing::MinimumCost,
+                DistanceMetric::Squared
+            ),
+            vec![(10, 10)]
+        );
+        assert_eq!(
+            distance(
+                &[0, 5, 10],
+                &[11, 4],
+                DistanceMetric::Maximum,
+                Pairing::MinimumCost
+            ),
+            Ok(1)
+        );
+        assert_eq!(
+            distance(
+                &[1, 2, 3, 4],
+                &[2, 4],
+                DistanceMetric::Absolute,
+                Pairing::MinimumCost
+            ),
+            Ok(0)
+        );
+    }
+
+    #[test]
+    fn test_distance_with_large_ids() {
+        let overflow = Err("Distance does not fit in 64 bits".to_string());
+
+        assert_eq!(
+            distance(
+                &[0, 0],
+                &[4_000_000_000; 2],
+                DistanceMetric::Squared,
+                Pairing::ByRank
+            ),
+            overflow
+        );
+        assert_eq!(
+            distance(
+                &[0],
+                &[3_000_000_000],
+                DistanceMetric::Squared,
+                Pairing::ByRank
+            ),
+            Ok(9_000_000_000_000_000_000)
+        );
+        assert_eq!(
+            distance(
+                &[i64::MIN],
+                &[i64::MAX],
+                DistanceMetric::Absolute,
+                Pairing::ByPosition
+            ),
+            overflow
+        );
+        assert_eq!(
+            distance(
+                &[i64::MAX],
+                &[i64::MAX - 5],
+                DistanceMetric::Maximum,
+                Pairing::ByRank
+            ),
+            Ok(5)
+        );
+        assert_eq!(
+            distance(
+                &[i64::MIN, 4_000_000_000],
+                &[4_000_000_000],
+                DistanceMetric::Squared,
+                Pairing::MinimumCost
+            ),
+            Ok(0)
+        );
+        assert_eq!(
+            part1("-9223372036854775808   9223372036854775807\n"),
+            Err("Distance does not fit in 64 bits".to_string())
+        );
+    }
+
+    #[test]
+    fn test_similarity_breakdown() {
+        assert_eq!(
+            similarity_breakdown_csv(TEST_INPUT),
+            Ok("\
+id,left_count,right_count,contribution
+3,3,3,27
+4,1,1,4
+1,1,0,0
+2,1,0,0
+"
+            .to_string())
+        );
+
+        let (left_list, right_list) = parse_two_lists(TEST_INPUT).unwrap();
+        let breakdown = similarity_breakdown(&left_list, &right_list);
+
+        assert_eq!(
+            breakdown
+                .iter()
+                .map(|entry| entry.contribution)
+                .sum::<i64>(),
+            similarity_score(&left_list, &right_list)
+        );
+    }
+
+    #[test]
+    fn test_reconciliation() {
+        let lists = parse_lists("3 4 3\n4 3 8\n2 5 2\n1 3 1\n3 9 3\n3 3 3\n", None).unwrap();
+        let reconciliation = Reconciliation::new(&lists).unwrap();
+
+        assert_eq!(reconciliation.distances[0][1], 11);
+        assert_eq!(reconciliation.distances[1][0], 11);
+        assert_eq!(reconciliation.distances[0][2], 4);
+        assert_eq!(reconciliation.distances[1][1], 0);
+        assert_eq!(reconciliation.similarity_scores[0][1], 31);
+        assert_eq!(reconciliation.similarity_scores[1][0], 4 + 3 * 3 * 3);
+        assert_eq!(reconciliation.unique_ids, vec![vec![], vec![5, 9], vec![8]]);
+    }
+
+    #[test]
+    fn test_sort_ids() {
+        let mut random = Xorshift::new(0x2024_1201);
+
+        for (length, range) in [(10, 100), (5000, 3000), (5000, 100_000), (5000, u64::MAX)] {
+            let mut list: Vec<i64> = (0..length)
+                .map(|_| match range {
+                    u64::MAX => random.next_u64() as i64,
+                    _ => random.below(range) as i64 - 1000,
+                })
+                .collect();
+            let mut expected = list.clone();
+
+            expected.sort();
+            sort_ids(&mut list);
+
+            assert_eq!(list, expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_two_lists_fast() {
+        assert_eq!(
+            parse_two_lists_fast(TEST_INPUT),
+            Some((vec![3, 4, 2, 1, 3, 3], vec![4, 3, 5, 3, 9, 3]))
+        );
+        assert_eq!(
+            parse_two_lists_fast("-3\t4\r\n\n  5 -6"),
+            Some((vec![-3, 5], vec![4, -6]))
+        );
+        assert_eq!(parse_two_lists_fast("3   4\n4\n"), None);
+        assert_eq!(parse_two_lists_fast("3   +4\n"), None);
+        assert_eq!(parse_two_lists_fast("99999999999999999999 1\n"), None);
+        assert_eq!(
+            part1("3   4\n4   x\n"),
+            Err("Line 2: unable to parse location ID \"x\"".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        assert_eq!(
+            parse_lists("3 4\n4\t\t3\r\n\n2   5\n", None),
+            Ok(vec![vec![3, 4, 2], vec![4, 3, 5]])
+        );
+        assert_eq!(
+            parse_lists("3, 4\n4,3\n", Some(",")),
+            Ok(vec![vec![3, 4], vec![4, 3]])
+        );
+        assert_eq!(
+            parse_lists("3   4\n4\n", None),
+            Err("Line 2: expected 2 columns but found 1".to_string())
+        );
+        assert_eq!(
+            parse_lists("3   4\n\n4   x\n", None),
+            Err("Line 3: unable to parse location ID \"x\"".to_string())
+        );
+        assert_eq!(
+            part1("3   4   5\n"),
+            Err("Expected 2 lists but found 3".to_string())
+        );
+        assert_eq!(part1(&TEST_INPUT.replace("   ", "\t")), Ok(11.to_string()));
+    }
 }
@@ -52,22 +52,24 @@ Thanks to the Problem Dampener, 4 reports are actually safe!
 Update your analysis by handling situations where the Problem Dampener can remove a single level from unsafe reports. How many reports are now safe?
 */
 
+use std::collections::BTreeMap;
+
+const MIN_LEVEL_CHANGE: i64 = 1;
 const MAX_LEVEL_CHANGE: i64 = 3;
 
 fn non_empty_lines(input: &str) -> impl Iterator<Item = &str> {
-    input.split('\n').filter(|line| !line.is_empty())
+    input.split('\n').filter(|line| !line.trim().is_empty())
 }
 
-#[derive(PartialEq)]
-enum LevelState {
-    Unset,
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Trend {
     Increasing,
     Decreasing,
 }
 
 fn parse_levels(level_string: &str) -> Vec<i64> {
     level_string
-        .split(' ')
+        .split_whitespace()
         .map(|level| match level.parse::<i64>() {
             Ok(value) => value,
             Err(_) => panic!("Unable to parse level: {:?}", level),
@@ -75,46 +77,481 @@ fn parse_levels(level_string: &str) -> Vec<i64> {
         .collect()
 }
 
-fn is_report_safe(report: &[i64]) -> bool {
-    let mut previous_level = 0;
-    let mut level_state = LevelState::Unset;
-    let report_length = report.len();
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UnsafeReason {
+    NoChange,
+    ChangeTooSmall { change: i64, limit: i64 },
+    ChangeTooLarge { change: i64, limit: i64 },
+    DirectionReversed,
+}
+
+impl std::fmt::Display for UnsafeReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            UnsafeReason::NoChange => write!(f, "neither an increase nor a decrease"),
+            UnsafeReason::ChangeTooSmall { change, limit } => {
+                write!(f, "change of {} is smaller than {}", change.abs(), limit)
+            }
+            UnsafeReason::ChangeTooLarge { change, limit } => {
+                write!(f, "change of {} is larger than {}", change.abs(), limit)
+            }
+            UnsafeReason::DirectionReversed => write!(f, "direction reversed"),
+        }
+    }
+}
+
+// The first adjacent pair of levels that breaks a rule, where `index` is the
+// position of the second level of the pair.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub index: usize,
+    pub levels: (i64, i64),
+    pub reason: UnsafeReason,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReportDiagnostic {
+    pub violation: Option<Violation>,
+    pub removed_level: Option<usize>,
+}
+
+impl ReportDiagnostic {
+    pub fn is_safe(&self) -> bool {
+        self.violation.is_none() || self.removed_level.is_some()
+    }
+}
+
+// How far apart adjacent levels may be for a report to be safe.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SafetyRules {
+    pub min_level_change: i64,
+    pub max_level_change: i64,
+}
+
+impl Default for SafetyRules {
+    fn default() -> Self {
+        Self {
+            min_level_change: MIN_LEVEL_CHANGE,
+            max_level_change: MAX_LEVEL_CHANGE,
+        }
+    }
+}
 
-    for (index, current_level) in report.iter().enumerate() {
-        if index > 0 {
-            let level_change = current_level - previous_level;
+impl SafetyRules {
+    fn is_step_allowed(&self, from: i64, to: i64, increasing: bool) -> bool {
+        let level_change = match increasing {
+            true => to - from,
+            false => from - to,
+        };
 
-            if level_change.abs() > MAX_LEVEL_CHANGE {
-                return false;
+        level_change >= self.min_level_change && level_change <= self.max_level_change
+    }
+
+    // Returns the direction of a safe report, which is unknown when it has fewer
+    // than two levels. A report without levels breaks no rule, so it is safe.
+    pub fn check(&self, report: &[i64]) -> Result<Option<Trend>, Violation> {
+        let mut trend: Option<Trend> = None;
+
+        for (index, pair) in report.windows(2).enumerate() {
+            let level_change = pair[1] - pair[0];
+            let violation = |reason| Violation {
+                index: index + 1,
+                levels: (pair[0], pair[1]),
+                reason,
+            };
+
+            if level_change.abs() > self.max_level_change {
+                return Err(violation(UnsafeReason::ChangeTooLarge {
+                    change: level_change,
+                    limit: self.max_level_change,
+                }));
             }
 
-            match level_change {
-                x if x > 0 => {
-                    if level_state == LevelState::Unset {
-                        level_state = LevelState::Decreasing;
-                    } else if level_state == LevelState::Increasing {
-                        return false;
-                    }
+            if level_change.abs() < self.min_level_change {
+                return Err(violation(match level_change {
+                    0 => UnsafeReason::NoChange,
+                    _ => UnsafeReason::ChangeTooSmall {
+                        change: level_change,
+                        limit: self.min_level_change,
+                    },
+                }));
+            }
+
+            let level_trend = match level_change {
+                x if x > 0 => Trend::Increasing,
+                x if x < 0 => Trend::Decreasing,
+                _ => continue,
+            };
+
+            match trend {
+                None => trend = Some(level_trend),
+                Some(trend) if trend != level_trend => {
+                    return Err(violation(UnsafeReason::DirectionReversed))
                 }
-                x if x < 0 => {
-                    if level_state == LevelState::Unset {
-                        level_state = LevelState::Increasing;
-                    } else if level_state == LevelState::Decreasing {
-                        return false;
+                Some(_) => {}
+            }
+        }
+
+        Ok(trend)
+    }
+
+    // Smallest number of levels to remove for the report to be safe, from the
+    // longest subsequence whose steps all follow the rules in one direction.
+    pub fn minimum_removals(&self, report: &[i64]) -> usize {
+        let mut longest_safe_subsequence = 0;
+
+        for increasing in [true, false] {
+            let mut longest_ending_at: Vec<usize> = Vec::with_capacity(report.len());
+
+            for (index, level) in report.iter().enumerate() {
+                let longest = (0..index)
+                    .filter(|&previous| self.is_step_allowed(report[previous], *level, increasing))
+                    .map(|previous| longest_ending_at[previous] + 1)
+                    .max()
+                    .unwrap_or(1);
+
+                longest_ending_at.push(longest);
+                longest_safe_subsequence = longest_safe_subsequence.max(longest);
+            }
+        }
+
+        report.len() - longest_safe_subsequence
+    }
+
+    pub fn is_safe_with_removals(&self, report: &[i64], removals: usize) -> bool {
+        match removals {
+            0 => self.check(report).is_ok(),
+            1 => self.is_safe_with_single_removal(report),
+            _ => self.minimum_removals(report) <= removals,
+        }
+    }
+
+    // Index of the first level breaking the rules in the given direction when the
+    // level at `skipped` is left out of the report.
+    fn first_unsafe_level(
+        &self,
+        report: &[i64],
+        increasing: bool,
+        skipped: Option<usize>,
+    ) -> Option<usize> {
+        let mut previous_level: Option<i64> = None;
+
+        for (index, &level) in report.iter().enumerate() {
+            if skipped == Some(index) {
+                continue;
+            }
+
+            if let Some(previous_level) = previous_level {
+                if !self.is_step_allowed(previous_level, level, increasing) {
+                    return Some(index);
+                }
+            }
+
+            previous_level = Some(level);
+        }
+
+        None
+    }
+
+    // Any level whose removal makes the report safe has to be one of the two
+    // levels of the first pair breaking the rules, so at most two more passes
+    // over the report are needed per direction.
+    pub fn is_safe_with_single_removal(&self, report: &[i64]) -> bool {
+        [true, false].into_iter().any(|increasing| {
+            match self.first_unsafe_level(report, increasing, None) {
+                Some(index) => [index - 1, index].into_iter().any(|skipped| {
+                    self.first_unsafe_level(report, increasing, Some(skipped))
+                        .is_none()
+                }),
+                None => true,
+            }
+        })
+    }
+
+    // Explains why a report is unsafe and, when the Problem Dampener is used,
+    // which level it removed to make the report safe. As in
+    // `is_safe_with_single_removal`, only the two levels around the first unsafe
+    // step in each direction can be the one to remove, and the lowest of those
+    // that works is reported.
+    pub fn diagnose(&self, report: &[i64], problem_dampener: bool) -> ReportDiagnostic {
+        let violation = self.check(report).err();
+        let mut removed_level = None;
+
+        if violation.is_some() && problem_dampener {
+            removed_level = [true, false]
+                .into_iter()
+                .filter_map(|increasing| {
+                    self.first_unsafe_level(report, increasing, None)
+                        .map(|index| (increasing, index))
+                })
+                .flat_map(|(increasing, index)| [(increasing, index - 1), (increasing, index)])
+                .filter(|&(increasing, skipped)| {
+                    self.first_unsafe_level(report, increasing, Some(skipped))
+                        .is_none()
+                })
+                .map(|(_, skipped)| skipped)
+                .min();
+        }
+
+        ReportDiagnostic {
+            violation,
+            removed_level,
+        }
+    }
+
+    // Number of levels in the longest contiguous part of the report that would
+    // be safe on its own.
+    pub fn longest_safe_run(&self, report: &[i64]) -> usize {
+        let mut longest_run = report.len().min(1);
+
+        for increasing in [true, false] {
+            let mut current_run = 1;
+
+            for pair in report.windows(2) {
+                match self.is_step_allowed(pair[0], pair[1], increasing) {
+                    true => current_run += 1,
+                    false => current_run = 1,
+                }
+
+                longest_run = longest_run.max(current_run);
+            }
+        }
+
+        longest_run
+    }
+}
+
+pub fn check_report(report: &[i64]) -> Result<Option<Trend>, Violation> {
+    SafetyRules::default().check(report)
+}
+
+pub fn count_safe_reports(input: &str, removals: usize, rules: &SafetyRules) -> usize {
+    non_empty_lines(input)
+        .filter(|line| rules.is_safe_with_removals(&parse_levels(line), removals))
+        .count()
+}
+
+fn is_report_safe(report: &[i64]) -> bool {
+    check_report(report).is_ok()
+}
+
+pub fn diagnose_report(report: &[i64], problem_dampener: bool) -> ReportDiagnostic {
+    SafetyRules::default().diagnose(report, problem_dampener)
+}
+
+fn report_table(input: &str, problem_dampener: bool) -> String {
+    let mut table = format!("{:>6}  {:<8}  {}\n", "line", "status", "details");
+
+    for (line_number, line) in non_empty_lines(input).enumerate() {
+        let report: Vec<i64> = parse_levels(line);
+        let diagnostic = diagnose_report(&report, problem_dampener);
+        let status = match diagnostic.is_safe() {
+            true => "safe",
+            false => "unsafe",
+        };
+        let mut details = String::new();
+
+        if let Some(violation) = diagnostic.violation {
+            details = format!(
+                "levels {} and {} ({} {}): {}",
+                violation.index,
+                violation.index + 1,
+                violation.levels.0,
+                violation.levels.1,
+                violation.reason
+            );
+        }
+
+        if let Some(index) = diagnostic.removed_level {
+            details.push_str(&format!(
+                "; safe after removing level {} ({})",
+                index + 1,
+                report[index]
+            ));
+        }
+
+        table.push_str(format!("{:>6}  {:<8}  {}", line_number + 1, status, details).trim_end());
+        table.push('\n');
+    }
+
+    table
+}
+
+pub fn part1_table(input: &str) -> Result<String, String> {
+    Ok(report_table(input, false))
+}
+
+pub fn part2_table(input: &str) -> Result<String, String> {
+    Ok(report_table(input, true))
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RuleFailures {
+    pub no_change: usize,
+    pub change_too_small: usize,
+    pub change_too_large: usize,
+    pub direction_reversed: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct UnsafeRun {
+    pub line: usize,
+    pub levels: usize,
+    pub longest_safe_run: usize,
+}
+
+// Statistics over every report of the input. Each unsafe report is counted
+// against the first rule it breaks.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ReportAnalytics {
+    pub reports: usize,
+    pub safe_reports: usize,
+    pub levels: usize,
+    pub step_sizes: BTreeMap<i64, usize>,
+    pub failures: RuleFailures,
+    pub unsafe_runs: Vec<UnsafeRun>,
+}
+
+impl ReportAnalytics {
+    pub fn new(input: &str, rules: &SafetyRules) -> Self {
+        let mut analytics = Self::default();
+
+        for (line_number, line) in non_empty_lines(input).enumerate() {
+            let report: Vec<i64> = parse_levels(line);
+
+            analytics.reports += 1;
+            analytics.levels += report.len();
+
+            for pair in report.windows(2) {
+                *analytics.step_sizes.entry(pair[1] - pair[0]).or_insert(0) += 1;
+            }
+
+            match rules.check(&report) {
+                Ok(_) => analytics.safe_reports += 1,
+                Err(violation) => {
+                    match violation.reason {
+                        UnsafeReason::NoChange => analytics.failures.no_change += 1,
+                        UnsafeReason::ChangeTooSmall { .. } => {
+                            analytics.failures.change_too_small += 1
+                        }
+                        UnsafeReason::ChangeTooLarge { .. } => {
+                            analytics.failures.change_too_large += 1
+                        }
+                        UnsafeReason::DirectionReversed => {
+                            analytics.failures.direction_reversed += 1
+                        }
                     }
+
+                    analytics.unsafe_runs.push(UnsafeRun {
+                        line: line_number + 1,
+                        levels: report.len(),
+                        longest_safe_run: rules.longest_safe_run(&report),
+                    });
                 }
-                _ => return false,
             }
         }
 
-        previous_level = *current_level;
+        analytics
+    }
 
-        if index == report_length - 1 {
-            return true;
+    fn levels_per_report(&self) -> f64 {
+        match self.reports {
+            0 => 0.0,
+            reports => self.levels as f64 / reports as f64,
         }
     }
 
-    false
+    fn rule_failures(&self) -> [(&'static str, usize); 4] {
+        [
+            ("no_change", self.failures.no_change),
+            ("change_too_small", self.failures.change_too_small),
+            ("change_too_large", self.failures.change_too_large),
+            ("direction_reversed", self.failures.direction_reversed),
+        ]
+    }
+
+    pub fn to_table(&self) -> String {
+        let mut table = format!(
+            "Reports: {} ({} safe, {} unsafe)\nLevels: {} ({:.2} per report)\n",
+            self.reports,
+            self.safe_reports,
+            self.reports - self.safe_reports,
+            self.levels,
+            self.levels_per_report()
+        );
+
+        table.push_str(&format!("\n{:>9}  {:>6}\n", "step size", "count"));
+
+        for (step_size, count) in self.step_sizes.iter() {
+            table.push_str(&format!("{:>9}  {:>6}\n", step_size, count));
+        }
+
+        table.push_str(&format!("\n{:<18}  {:>6}\n", "failed rule", "count"));
+
+        for (rule, count) in self.rule_failures() {
+            table.push_str(&format!("{:<18}  {:>6}\n", rule, count));
+        }
+
+        table.push_str(&format!(
+            "\n{:>6}  {:>6}  {:>16}\n",
+            "line", "levels", "longest safe run"
+        ));
+
+        for unsafe_run in self.unsafe_runs.iter() {
+            table.push_str(&format!(
+                "{:>6}  {:>6}  {:>16}\n",
+                unsafe_run.line, unsafe_run.levels, unsafe_run.longest_safe_run
+            ));
+        }
+
+        table
+    }
+
+    pub fn to_json(&self) -> String {
+        let step_sizes: Vec<String> = self
+            .step_sizes
+            .iter()
+            .map(|(step_size, count)| format!("\"{}\": {}", step_size, count))
+            .collect();
+        let failures: Vec<String> = self
+            .rule_failures()
+            .iter()
+            .map(|(rule, count)| format!("\"{}\": {}", rule, count))
+            .collect();
+        let unsafe_runs: Vec<String> = self
+            .unsafe_runs
+            .iter()
+            .map(|unsafe_run| {
+                format!(
+                    "{{\"line\": {}, \"levels\": {}, \"longest_safe_run\": {}}}",
+                    unsafe_run.line, unsafe_run.levels, unsafe_run.longest_safe_run
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"reports\": {}, \"safe_reports\": {}, \"unsafe_reports\": {}, \"levels\": {}, \"levels_per_report\": {:.2}, \"step_sizes\": {{{}}}, \"failures\": {{{}}}, \"unsafe_runs\": [{}]}}",
+            self.reports,
+            self.safe_reports,
+            self.reports - self.safe_reports,
+            self.levels,
+            self.levels_per_report(),
+            step_sizes.join(", "),
+            failures.join(", "),
+            unsafe_runs.join(", ")
+        )
+    }
+}
+
+pub fn analytics_table(input: &str) -> Result<String, String> {
+    Ok(ReportAnalytics::new(input, &SafetyRules::default()).to_table())
+}
+
+pub fn analytics_json(input: &str) -> Result<String, String> {
+    Ok(format!(
+        "{}\n",
+        ReportAnalytics::new(input, &SafetyRules::default()).to_json()
+    ))
 }
 
 pub fn part1(input: &str) -> Result<String, String> {
@@ -132,24 +569,14 @@ pub fn part1(input: &str) -> Result<String, String> {
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
+    let rules = SafetyRules::default();
     let mut count_of_safe_reports = 0;
 
     for line in non_empty_lines(input) {
         let report: Vec<i64> = parse_levels(line);
 
-        match is_report_safe(&report) {
-            false => {
-                for index in 0..report.len() {
-                    let mut report_with_removed_level = report.clone();
-                    report_with_removed_level.remove(index);
-
-                    if is_report_safe(&report_with_removed_level) {
-                        count_of_safe_reports += 1;
-                        break;
-                    }
-                }
-            }
-            true => count_of_safe_reports += 1,
+        if rules.is_safe_with_single_removal(&report) {
+            count_of_safe_reports += 1;
         }
     }
 
@@ -158,7 +585,8 @@ pub fn part2(input: &str) -> Result<String, String> {
 
 #[cfg(test)]
 mod tests {
-    use crate::day2::{part1, part2};
+    use super::*;
+    use crate::test_utils::Xorshift;
 
     static TEST_INPUT: &str = "\
 7 6 4 2 1
@@ -178,4 +606,200 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(4.to_string()));
     }
+
+    #[test]
+    fn test_diagnose_report() {
+        assert_eq!(
+            diagnose_report(&[1, 2, 7, 8, 9], true),
+            ReportDiagnostic {
+                violation: Some(Violation {
+                    index: 2,
+                    levels: (2, 7),
+                    reason: UnsafeReason::ChangeTooLarge {
+                        change: 5,
+                        limit: MAX_LEVEL_CHANGE,
+                    },
+                }),
+                removed_level: None,
+            }
+        );
+        assert_eq!(
+            diagnose_report(&[1, 3, 2, 4, 5], true),
+            ReportDiagnostic {
+                violation: Some(Violation {
+                    index: 2,
+                    levels: (3, 2),
+                    reason: UnsafeReason::DirectionReversed,
+                }),
+                removed_level: Some(1),
+            }
+        );
+        assert_eq!(
+            check_report(&[8, 6, 4, 4, 1]).unwrap_err().reason,
+            UnsafeReason::NoChange
+        );
+        assert!(diagnose_report(&[7, 6, 4, 2, 1], false).is_safe());
+
+        assert!(is_report_safe(&[]));
+        assert!(diagnose_report(&[], true).is_safe());
+        assert_eq!(SafetyRules::default().minimum_removals(&[]), 0);
+        assert_eq!(diagnose_report(&[9, 1], true).removed_level, Some(0));
+
+        let wide_rules = SafetyRules {
+            min_level_change: 2,
+            max_level_change: 6,
+        };
+        assert_eq!(
+            wide_rules.diagnose(&[1, 3, 4, 8], true).removed_level,
+            Some(1)
+        );
+        assert_eq!(diagnose_report(&[1, 3, 4, 8], true).removed_level, Some(3));
+    }
+
+    #[test]
+    fn test_minimum_removals() {
+        let rules = SafetyRules::default();
+
+        assert_eq!(rules.minimum_removals(&[7, 6, 4, 2, 1]), 0);
+        assert_eq!(rules.minimum_removals(&[1, 3, 2, 4, 5]), 1);
+        assert_eq!(rules.minimum_removals(&[1, 2, 7, 8, 9]), 2);
+        assert_eq!(rules.minimum_removals(&[9, 1, 8, 2, 7, 3, 6]), 3);
+        assert_eq!(rules.minimum_removals(&[5]), 0);
+        assert_eq!(rules.minimum_removals(&[]), 0);
+
+        assert_eq!(count_safe_reports(TEST_INPUT, 0, &rules), 2);
+        assert_eq!(count_safe_reports(TEST_INPUT, 1, &rules), 4);
+        assert_eq!(count_safe_reports(TEST_INPUT, 2, &rules), 6);
+
+        let wide_rules = SafetyRules {
+            min_level_change: 2,
+            max_level_change: 5,
+        };
+
+        assert_eq!(wide_rules.minimum_removals(&[1, 2, 7, 8, 9]), 2);
+        assert_eq!(
+            wide_rules.check(&[1, 3, 4]).unwrap_err().reason,
+            UnsafeReason::ChangeTooSmall {
+                change: 1,
+                limit: 2
+            }
+        );
+        assert_eq!(count_safe_reports(TEST_INPUT, 0, &wide_rules), 0);
+    }
+
+    fn first_removal_brute_force(report: &[i64]) -> Option<usize> {
+        (0..report.len()).find(|&index| {
+            let mut report_with_removed_level = report.to_vec();
+            report_with_removed_level.remove(index);
+
+            is_report_safe(&report_with_removed_level)
+        })
+    }
+
+    fn is_safe_with_single_removal_brute_force(report: &[i64]) -> bool {
+        is_report_safe(report) || first_removal_brute_force(report).is_some()
+    }
+
+    #[test]
+    fn test_single_removal_matches_brute_force_on_random_reports() {
+        let rules = SafetyRules::default();
+        let mut random = Xorshift::new(0x2024_1202);
+
+        for _ in 0..5000 {
+            let length = 1 + random.below(10) as usize;
+            let mut report: Vec<i64> = vec![random.below(20) as i64];
+
+            for _ in 1..length {
+                let step = random.below(11) as i64 - 5;
+                report.push(report[report.len() - 1] + step);
+            }
+
+            assert_eq!(
+                rules.is_safe_with_single_removal(&report),
+                is_safe_with_single_removal_brute_force(&report),
+                "{:?}",
+                report
+            );
+            assert_eq!(
+                rules.minimum_removals(&report) <= 1,
+                is_safe_with_single_removal_brute_force(&report),
+                "{:?}",
+                report
+            );
+
+            if !is_report_safe(&report) {
+                assert_eq!(
+                    rules.diagnose(&report, true).removed_level,
+                    first_removal_brute_force(&report),
+                    "{:?}",
+                    report
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_report_trend() {
+        assert_eq!(check_report(&[1, 3, 6, 7, 9]), Ok(Some(Trend::Increasing)));
+        assert_eq!(check_report(&[7, 6, 4, 2, 1]), Ok(Some(Trend::Decreasing)));
+        assert_eq!(check_report(&[5]), Ok(None));
+    }
+
+    #[test]
+    fn test_flexible_whitespace() {
+        let input =
+            "7  6 4\t2 1\r\n1 2 7 8 9\n  9 7 6 2 1  \n\t\n1 3 2 4 5\n8 6  4 4 1\n1\t3\t6\t7\t9\n";
+
+        assert_eq!(part1(input), Ok(2.to_string()));
+        assert_eq!(part2(input), Ok(4.to_string()));
+        assert_eq!(parse_levels("1\t\t2   3\r"), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_report_analytics() {
+        let analytics = ReportAnalytics::new(TEST_INPUT, &SafetyRules::default());
+
+        assert_eq!(analytics.reports, 6);
+        assert_eq!(analytics.safe_reports, 2);
+        assert_eq!(analytics.levels, 30);
+        assert_eq!(analytics.step_sizes.get(&5), Some(&1));
+        assert_eq!(analytics.step_sizes.values().sum::<usize>(), 24);
+        assert_eq!(
+            analytics.failures,
+            RuleFailures {
+                no_change: 1,
+                change_too_small: 0,
+                change_too_large: 2,
+                direction_reversed: 1,
+            }
+        );
+        assert_eq!(
+            analytics
+                .unsafe_runs
+                .iter()
+                .map(|unsafe_run| (unsafe_run.line, unsafe_run.longest_safe_run))
+                .collect::<Vec<(usize, usize)>>(),
+            vec![(2, 3), (3, 3), (4, 3), (5, 3)]
+        );
+        assert_eq!(
+            analytics_json("1 2 2\n"),
+            Ok("{\"reports\": 1, \"safe_reports\": 0, \"unsafe_reports\": 1, \"levels\": 3, \"levels_per_report\": 3.00, \"step_sizes\": {\"0\": 1, \"1\": 1}, \"failures\": {\"no_change\": 1, \"change_too_small\": 0, \"change_too_large\": 0, \"direction_reversed\": 0}, \"unsafe_runs\": [{\"line\": 1, \"levels\": 3, \"longest_safe_run\": 2}]}\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_part2_table() {
+        assert_eq!(
+            part2_table(TEST_INPUT),
+            Ok("  line  status    details
+     1  safe
+     2  unsafe    levels 2 and 3 (2 7): change of 5 is larger than 3
+     3  unsafe    levels 3 and 4 (6 2): change of 4 is larger than 3
+     4  safe      levels 2 and 3 (3 2): direction reversed; safe after removing level 2 (3)
+     5  safe      levels 3 and 4 (4 4): neither an increase nor a decrease; safe after removing level 3 (4)
+     6  safe
+"
+            .to_string())
+        );
+    }
 }
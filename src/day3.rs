@@ -36,154 +36,417 @@ This time, the sum of the results is 48 (2*4 + 8*5).
 Handle the new instructions; what do you get if you add up all of the results of just the enabled multiplications?
 */
 
-#[derive(PartialEq)]
-enum State {
-    FindM,
-    FindU,
-    FindL,
-    FindOpenedBracket,
-    FindFirstDigit,
-    FindLastDigit,
-    FindO,
-    FindNOrOpenedBracket,
-    FindClosedBracket,
-    FindSingleQuote,
-    FindT,
-}
-
-fn reset_state(state: &mut State, first_number: &mut i64) {
-    *state = State::FindM;
-    *first_number = -1;
-}
-
-fn parse_number(number_string: &str) -> i64 {
-    match number_string.parse::<i64>() {
-        Ok(number) => number,
-        Err(_) => panic!("Unable to parse number: {:?}", number_string),
-    }
-}
-
-fn calculate_sum(input: &str, mul_instructions_deactivatable: bool) -> i64 {
-    let mut total_sum = 0;
-    let mut state = State::FindM;
-    let mut index_of_first_digit = 0;
-    let mut first_number = -1;
-    let mut mul_instructions_enabled = true;
-
-    for (index, character) in input.as_bytes().iter().enumerate() {
-        match character {
-            b'm' => {
-                if state == State::FindM {
-                    state = State::FindU
-                }
-            }
-            b'u' => {
-                if state == State::FindU {
-                    state = State::FindL
-                } else {
-                    reset_state(&mut state, &mut first_number);
-                }
+use std::io::{self, Read};
+use std::ops::Range;
+
+const MAX_OPERAND_DIGITS: usize = 3;
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Clone, Copy)]
+pub enum Effect {
+    Accumulate(fn(&[i64]) -> i64),
+    Enable,
+    Disable,
+}
+
+#[derive(Clone, Copy)]
+pub struct InstructionDefinition {
+    pub name: &'static str,
+    pub arity: usize,
+    pub effect: Effect,
+}
+
+#[derive(Clone, Default)]
+pub struct InstructionSet {
+    definitions: Vec<InstructionDefinition>,
+}
+
+impl InstructionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, name: &'static str, arity: usize, effect: Effect) -> Self {
+        self.definitions.push(InstructionDefinition {
+            name,
+            arity,
+            effect,
+        });
+
+        self
+    }
+
+    pub fn definitions(&self) -> &[InstructionDefinition] {
+        &self.definitions
+    }
+
+    pub fn multiplications() -> Self {
+        Self::new().with(
+            "mul",
+            2,
+            Effect::Accumulate(|arguments| arguments[0] * arguments[1]),
+        )
+    }
+
+    // Longest byte sequence any instruction of the set can span, which bounds how
+    // much input must be kept around to recognise an instruction.
+    pub fn max_instruction_length(&self) -> usize {
+        self.definitions
+            .iter()
+            .map(|definition| {
+                definition.name.len()
+                    + 2
+                    + definition.arity * MAX_OPERAND_DIGITS
+                    + definition.arity.saturating_sub(1)
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    pub fn conditional_multiplications() -> Self {
+        Self::multiplications()
+            .with("do", 0, Effect::Enable)
+            .with("don't", 0, Effect::Disable)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Instruction {
+    pub definition: usize,
+    pub name: &'static str,
+    pub arguments: Vec<i64>,
+    pub span: Range<usize>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NearMiss {
+    pub name: &'static str,
+    pub span: Range<usize>,
+    pub reason: String,
+}
+
+// Yields every well-formed instruction of the set found in the corrupted memory,
+// skipping over anything else one byte at a time.
+pub struct Lexer<'a> {
+    input: &'a [u8],
+    position: usize,
+    scan_limit: usize,
+    instruction_set: &'a InstructionSet,
+    near_misses: Vec<NearMiss>,
+}
+
+impl<'a> Lexer<'a> {
+    pub fn new(input: &'a str, instruction_set: &'a InstructionSet) -> Self {
+        Self::from_bytes(input.as_bytes(), instruction_set)
+    }
+
+    pub fn from_bytes(input: &'a [u8], instruction_set: &'a InstructionSet) -> Self {
+        Self {
+            input,
+            position: 0,
+            scan_limit: input.len(),
+            instruction_set,
+            near_misses: Vec::new(),
+        }
+    }
+
+    fn parse_arguments(
+        &self,
+        start: usize,
+        arity: usize,
+    ) -> Result<(Vec<i64>, usize), (usize, String)> {
+        let mut arguments: Vec<i64> = Vec::with_capacity(arity);
+        let mut position = start;
+
+        for argument_index in 0..arity {
+            let digits = self.input[position..]
+                .iter()
+                .take_while(|character| character.is_ascii_digit())
+                .count();
+
+            if digits == 0 {
+                return Err((position, self.expected("a digit", position)));
             }
-            b'l' => {
-                if state == State::FindL {
-                    state = State::FindOpenedBracket
-                } else {
-                    reset_state(&mut state, &mut first_number);
-                }
+
+            if digits > MAX_OPERAND_DIGITS {
+                return Err((
+                    position + MAX_OPERAND_DIGITS,
+                    format!("operand has more than {} digits", MAX_OPERAND_DIGITS),
+                ));
             }
-            b'(' => {
-                if state == State::FindOpenedBracket {
-                    state = State::FindFirstDigit
-                } else if state == State::FindNOrOpenedBracket {
-                    state = State::FindClosedBracket
-                } else {
-                    reset_state(&mut state, &mut first_number);
-                }
+
+            match parse_number(&self.input[position..position + digits]) {
+                Some(number) => arguments.push(number),
+                None => return Err((position + digits - 1, "number is too large".to_string())),
             }
-            b'0'..=b'9' => {
-                if state == State::FindFirstDigit {
-                    state = State::FindLastDigit;
-                    index_of_first_digit = index;
-                } else if state == State::FindLastDigit {
-                } else {
-                    reset_state(&mut state, &mut first_number);
+
+            position += digits;
+
+            if argument_index + 1 < arity {
+                if self.input.get(position) != Some(&b',') {
+                    return Err((position, self.expected("','", position)));
                 }
+
+                position += 1;
             }
-            b',' => {
-                if state == State::FindLastDigit {
-                    state = State::FindFirstDigit;
-                    first_number = parse_number(&input[index_of_first_digit..index]);
-                } else {
-                    reset_state(&mut state, &mut first_number);
-                }
+        }
+
+        match self.input.get(position) {
+            Some(b')') => Ok((arguments, position + 1)),
+            _ => Err((position, self.expected("')'", position))),
+        }
+    }
+
+    fn expected(&self, expected: &str, position: usize) -> String {
+        match self.input.get(position) {
+            Some(&character) => format!("expected {} but found {:?}", expected, character as char),
+            None => format!("expected {} but reached the end of the input", expected),
+        }
+    }
+
+    // A name that matches without the rest of the instruction being well-formed
+    // is a near miss. When several names match, the longest one explains it.
+    fn instruction_at(&self, start: usize) -> Result<Instruction, Option<NearMiss>> {
+        let mut near_miss: Option<NearMiss> = None;
+
+        for (definition, instruction) in self.instruction_set.definitions.iter().enumerate() {
+            let name = instruction.name.as_bytes();
+            let opened_bracket = start + name.len();
+
+            if !self.input[start..].starts_with(name) {
+                continue;
             }
-            b')' => {
-                if state == State::FindLastDigit && first_number > -1 {
-                    state = State::FindM;
-                    let second_number = parse_number(&input[index_of_first_digit..index]);
 
-                    if mul_instructions_enabled {
-                        total_sum += first_number * second_number;
+            let rejection = if self.input.get(opened_bracket) != Some(&b'(') {
+                (opened_bracket, self.expected("'('", opened_bracket))
+            } else {
+                match self.parse_arguments(opened_bracket + 1, instruction.arity) {
+                    Ok((arguments, end)) => {
+                        return Ok(Instruction {
+                            definition,
+                            name: instruction.name,
+                            arguments,
+                            span: start..end,
+                        })
                     }
-                } else if state == State::FindClosedBracket {
-                    mul_instructions_enabled = true;
-                    state = State::FindM
-                } else {
-                    reset_state(&mut state, &mut first_number);
-                }
-            }
-            b'd' => {
-                reset_state(&mut state, &mut first_number);
-                state = State::FindO;
-            }
-            b'o' => {
-                if state == State::FindO {
-                    state = State::FindNOrOpenedBracket;
-                } else {
-                    reset_state(&mut state, &mut first_number);
+                    Err(rejection) => rejection,
                 }
+            };
+
+            if near_miss
+                .as_ref()
+                .is_none_or(|near_miss| near_miss.name.len() < instruction.name.len())
+            {
+                let (position, reason) = rejection;
+
+                near_miss = Some(NearMiss {
+                    name: instruction.name,
+                    span: start..(position + 1).min(self.input.len()),
+                    reason,
+                });
             }
-            b'n' => {
-                if state == State::FindNOrOpenedBracket {
-                    state = State::FindSingleQuote;
-                } else {
-                    reset_state(&mut state, &mut first_number);
+        }
+
+        Err(near_miss)
+    }
+
+    pub fn near_misses(&self) -> &[NearMiss] {
+        &self.near_misses
+    }
+}
+
+impl Iterator for Lexer<'_> {
+    type Item = Instruction;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.scan_limit {
+            match self.instruction_at(self.position) {
+                Ok(instruction) => {
+                    self.position = instruction.span.end;
+                    return Some(instruction);
                 }
-            }
-            b'\'' => {
-                if state == State::FindSingleQuote {
-                    state = State::FindT;
-                } else {
-                    reset_state(&mut state, &mut first_number);
+                Err(near_miss) => {
+                    self.near_misses.extend(near_miss);
+                    self.position += 1;
                 }
             }
-            b't' => {
-                if state == State::FindT {
-                    if mul_instructions_deactivatable {
-                        mul_instructions_enabled = false;
-                    }
+        }
 
-                    state = State::FindM
-                }
+        None
+    }
+}
+
+fn parse_number(digits: &[u8]) -> Option<i64> {
+    std::str::from_utf8(digits).ok()?.parse::<i64>().ok()
+}
+
+pub struct Interpreter<'a> {
+    instruction_set: &'a InstructionSet,
+    enabled: bool,
+    total_sum: i64,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(instruction_set: &'a InstructionSet) -> Self {
+        Self {
+            instruction_set,
+            enabled: true,
+            total_sum: 0,
+        }
+    }
+
+    // Applies one instruction and returns what it added to the sum.
+    pub fn execute(&mut self, instruction: &Instruction) -> i64 {
+        match self.instruction_set.definitions[instruction.definition].effect {
+            Effect::Accumulate(operation) if self.enabled => {
+                let contribution = operation(&instruction.arguments);
+                self.total_sum += contribution;
+                contribution
+            }
+            Effect::Accumulate(_) => 0,
+            Effect::Enable => {
+                self.enabled = true;
+                0
+            }
+            Effect::Disable => {
+                self.enabled = false;
+                0
             }
-            _ => state = State::FindM,
         }
     }
 
-    total_sum
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn total_sum(&self) -> i64 {
+        self.total_sum
+    }
+}
+
+pub fn evaluate(input: &str, instruction_set: &InstructionSet) -> i64 {
+    let mut interpreter = Interpreter::new(instruction_set);
+
+    for instruction in Lexer::new(input, instruction_set) {
+        interpreter.execute(&instruction);
+    }
+
+    interpreter.total_sum()
+}
+
+// Evaluates the memory read from `reader` a chunk at a time, with the same
+// semantics as `evaluate`. Only the bytes of an instruction that may still be
+// cut by the end of the current chunk are carried over to the next one.
+pub fn evaluate_reader<R: Read>(reader: R, instruction_set: &InstructionSet) -> io::Result<i64> {
+    evaluate_reader_in_chunks(reader, instruction_set, READ_CHUNK_SIZE)
+}
+
+fn evaluate_reader_in_chunks<R: Read>(
+    mut reader: R,
+    instruction_set: &InstructionSet,
+    chunk_size: usize,
+) -> io::Result<i64> {
+    let max_instruction_length = instruction_set.max_instruction_length();
+    let mut interpreter = Interpreter::new(instruction_set);
+    let mut buffer: Vec<u8> = Vec::with_capacity(chunk_size + max_instruction_length);
+    let mut chunk = vec![0; chunk_size];
+
+    loop {
+        let bytes_read = match reader.read(&mut chunk) {
+            Ok(bytes_read) => bytes_read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        let end_of_input = bytes_read == 0;
+
+        buffer.extend_from_slice(&chunk[..bytes_read]);
+
+        let mut lexer = Lexer::from_bytes(&buffer, instruction_set);
+
+        if !end_of_input {
+            lexer.scan_limit = buffer.len().saturating_sub(max_instruction_length - 1);
+        }
+
+        for instruction in lexer.by_ref() {
+            interpreter.execute(&instruction);
+        }
+
+        let consumed = lexer.position.min(buffer.len());
+        buffer.drain(..consumed);
+
+        if end_of_input {
+            return Ok(interpreter.total_sum());
+        }
+    }
+}
+
+// Lists every recognised instruction with its byte offset, whether instructions
+// were enabled when it was reached and what it added to the sum, followed by the
+// near misses that were rejected.
+pub fn disassemble(input: &str, instruction_set: &InstructionSet) -> String {
+    let mut interpreter = Interpreter::new(instruction_set);
+    let mut lexer = Lexer::new(input, instruction_set);
+    let mut listing = format!(
+        "{:>8}  {:<8}  {:<24}  {}\n",
+        "offset", "state", "instruction", "contribution"
+    );
+
+    for instruction in lexer.by_ref() {
+        let state = match interpreter.enabled() {
+            true => "enabled",
+            false => "disabled",
+        };
+        let contribution = interpreter.execute(&instruction);
+
+        listing.push_str(&format!(
+            "{:>8}  {:<8}  {:<24}  {}\n",
+            instruction.span.start,
+            state,
+            &input[instruction.span.clone()],
+            contribution
+        ));
+    }
+
+    listing.push_str(&format!("\nTotal: {}\n", interpreter.total_sum()));
+    listing.push_str(&format!("\nNear misses: {}\n", lexer.near_misses().len()));
+
+    for near_miss in lexer.near_misses() {
+        listing.push_str(&format!(
+            "{:>8}  {:<24}  {}\n",
+            near_miss.span.start,
+            format!(
+                "{:?}",
+                String::from_utf8_lossy(&input.as_bytes()[near_miss.span.clone()])
+            ),
+            near_miss.reason
+        ));
+    }
+
+    listing
+}
+
+pub fn part1_disasm(input: &str) -> Result<String, String> {
+    Ok(disassemble(input, &InstructionSet::multiplications()))
+}
+
+pub fn part2_disasm(input: &str) -> Result<String, String> {
+    Ok(disassemble(
+        input,
+        &InstructionSet::conditional_multiplications(),
+    ))
 }
 
 pub fn part1(input: &str) -> Result<String, String> {
-    Ok(calculate_sum(input, false).to_string())
+    Ok(evaluate(input, &InstructionSet::multiplications()).to_string())
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
-    Ok(calculate_sum(input, true).to_string())
+    Ok(evaluate(input, &InstructionSet::conditional_multiplications()).to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::day3::{part1, part2};
+    use super::*;
 
     static TEST_INPUT: &str = "\
 xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
@@ -202,4 +465,158 @@ xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT_2), Ok(48.to_string()));
     }
+
+    // (input, part 1, part 2)
+    static CONFORMANCE_CASES: [(&str, i64, i64); 20] = [
+        ("mul(2,3)", 6, 6),
+        ("mul(123,456)", 56088, 56088),
+        ("mul(002,030)", 60, 60),
+        ("mul(1234,5)", 0, 0),
+        ("mul(5,1234)", 0, 0),
+        ("mul(mul(1,2),3)", 2, 2),
+        ("mul(,3)mul(2,)mul()", 0, 0),
+        ("mul(-2,3)mul(2, 3)mul ( 2 , 4 )", 0, 0),
+        ("mul(2,3", 0, 0),
+        ("mmul(2,3)", 6, 6),
+        ("dmul(2,3)", 6, 6),
+        ("tmul(2,3)t", 6, 6),
+        ("don'tmul(2,3)", 6, 6),
+        ("don't()mul(2,3)", 6, 0),
+        ("don't()\nmul(2,3)", 6, 0),
+        ("don'\nt()mul(2,3)", 6, 6),
+        ("don't()do()mul(2,3)", 6, 6),
+        ("do()don't()mul(2,3)", 6, 0),
+        ("don't(mul(2,3)", 6, 6),
+        ("don't()mul(1,1)do(mul(2,3)do()mul(4,5)", 27, 20),
+    ];
+
+    #[test]
+    fn test_conformance() {
+        for (input, expected_part1, expected_part2) in CONFORMANCE_CASES {
+            assert_eq!(
+                part1(input),
+                Ok(expected_part1.to_string()),
+                "part 1 of {:?}",
+                input
+            );
+            assert_eq!(
+                part2(input),
+                Ok(expected_part2.to_string()),
+                "part 2 of {:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_evaluate_reader() {
+        let instruction_set = InstructionSet::conditional_multiplications();
+
+        assert_eq!(
+            evaluate_reader(TEST_INPUT_2.as_bytes(), &instruction_set).unwrap(),
+            48
+        );
+
+        for chunk_size in 1..=20 {
+            for (input, _, expected_part2) in CONFORMANCE_CASES
+                .iter()
+                .chain([(TEST_INPUT, 161, 161), (TEST_INPUT_2, 161, 48)].iter())
+            {
+                assert_eq!(
+                    evaluate_reader_in_chunks(input.as_bytes(), &instruction_set, chunk_size)
+                        .unwrap(),
+                    *expected_part2,
+                    "{:?} in chunks of {}",
+                    input,
+                    chunk_size
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lexer() {
+        let instruction_set = InstructionSet::conditional_multiplications();
+        let instructions: Vec<Instruction> = Lexer::new(TEST_INPUT_2, &instruction_set).collect();
+
+        assert_eq!(instructions.len(), 6);
+        assert_eq!(
+            instructions[0],
+            Instruction {
+                definition: 0,
+                name: "mul",
+                arguments: vec![2, 4],
+                span: 1..9,
+            }
+        );
+        assert_eq!(instructions[1].name, "don't");
+        assert_eq!(instructions[1].span, 20..27);
+        assert_eq!(instructions[4].name, "do");
+    }
+
+    #[test]
+    fn test_near_misses() {
+        let instruction_set = InstructionSet::conditional_multiplications();
+        let mut lexer = Lexer::new("mul(32,64]mul ( 2 , 4 )don't_do()", &instruction_set);
+
+        assert_eq!(lexer.by_ref().count(), 1);
+        assert_eq!(
+            lexer.near_misses(),
+            &[
+                NearMiss {
+                    name: "mul",
+                    span: 0..10,
+                    reason: "expected ')' but found ']'".to_string(),
+                },
+                NearMiss {
+                    name: "mul",
+                    span: 10..14,
+                    reason: "expected '(' but found ' '".to_string(),
+                },
+                NearMiss {
+                    name: "don't",
+                    span: 23..29,
+                    reason: "expected '(' but found '_'".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let listing = part2_disasm(TEST_INPUT_2).unwrap();
+        let lines: Vec<&str> = listing.lines().collect();
+
+        assert_eq!(lines[1], "       1  enabled   mul(2,4)                  8");
+        assert_eq!(lines[3], "      28  disabled  mul(5,5)                  0");
+        assert!(listing.contains("Total: 48"));
+        assert_eq!(
+            lines[lines.len() - 1],
+            "      37  \"mul(32,64]\"              expected ')' but found ']'"
+        );
+    }
+
+    #[test]
+    fn test_custom_instructions() {
+        let instruction_set = InstructionSet::conditional_multiplications()
+            .with(
+                "add",
+                2,
+                Effect::Accumulate(|arguments| arguments[0] + arguments[1]),
+            )
+            .with("neg", 1, Effect::Accumulate(|arguments| -arguments[0]))
+            .with(
+                "sum",
+                3,
+                Effect::Accumulate(|arguments| arguments.iter().sum()),
+            );
+
+        assert_eq!(
+            evaluate(
+                "add(1,2)neg(5)don't()add(100,1)do()sum(1,2,3)sum(1,2)mul(2,3)",
+                &instruction_set
+            ),
+            1 + 2 - 5 + 6 + 6
+        );
+    }
 }
@@ -70,6 +70,9 @@ In this example, an X-MAS appears 9 times.
 Flip the word search from the instructions back over to the word search side and try again. How many times does an X-MAS appear?
 */
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
 /*
 00, 01, 02, 03, 04, 05, 06, 07, 08, 09, 10
 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21
@@ -105,26 +108,98 @@ valid? 54 mod 11 = 10
 invalid if : index mod row_length = row_length - 1
 */
 
-struct XMASGrid<'a> {
-    pub input_bytes: &'a [u8],
+// Splits a grid into rows, accepting '\r\n' line endings and a missing final
+// newline.
+fn grid_rows(input: &str) -> Result<Vec<&str>, String> {
+    let mut rows: Vec<&str> = input
+        .split('\n')
+        .map(|line| line.strip_suffix('\r').unwrap_or(line))
+        .collect();
+
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    if rows.is_empty() {
+        return Err("Unable to determine row length".to_string());
+    }
+
+    Ok(rows)
+}
+
+// Returns the length every row should have, taken from the first row.
+fn check_row_lengths(row_lengths: impl Iterator<Item = usize>) -> Result<usize, String> {
+    let mut expected_length = None;
+
+    for (row_number, row_length) in row_lengths.enumerate() {
+        match expected_length {
+            None => expected_length = Some(row_length),
+            Some(expected_length) if row_length != expected_length => {
+                return Err(format!(
+                    "Row {} has {} characters, expected {}",
+                    row_number + 1,
+                    row_length,
+                    expected_length
+                ))
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(expected_length.unwrap_or(0))
+}
+
+pub struct XMASGrid<'a> {
+    pub input_bytes: Cow<'a, [u8]>,
     row_length: usize,
     total_length: usize,
 }
 
 impl<'a> XMASGrid<'a> {
     pub fn new(input: &'a str) -> Self {
-        let input_bytes = input.as_bytes();
-        let row_length = match input.find('\n') {
-            Some(length) => length,
-            None => panic!("Unable to determine row length"),
+        match Self::parse(input, false) {
+            Ok(grid) => grid,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    // Accepts '\r\n' line endings and a missing final newline. Rows of uneven
+    // length are rejected unless `pad_ragged_rows` is set, in which case they are
+    // filled with '.' up to the longest row.
+    pub fn parse(input: &'a str, pad_ragged_rows: bool) -> Result<Self, String> {
+        let rows: Vec<&[u8]> = grid_rows(input)?
+            .into_iter()
+            .map(|row| row.as_bytes())
+            .collect();
+
+        let row_length = match pad_ragged_rows {
+            true => rows.iter().map(|row| row.len()).max().unwrap_or(0),
+            false => check_row_lengths(rows.iter().map(|row| row.len()))?,
         };
-        let total_length = input.len();
 
-        Self {
+        let total_length = rows.len() * (row_length + 1);
+        let input_bytes = if input.len() == total_length
+            && !input.contains('\r')
+            && rows.iter().all(|row| row.len() == row_length)
+        {
+            Cow::Borrowed(input.as_bytes())
+        } else {
+            let mut normalised: Vec<u8> = Vec::with_capacity(total_length);
+
+            for row in rows {
+                normalised.extend_from_slice(row);
+                normalised.resize(normalised.len() + row_length - row.len(), b'.');
+                normalised.push(b'\n');
+            }
+
+            Cow::Owned(normalised)
+        };
+
+        Ok(Self {
             input_bytes,
             row_length,
             total_length,
-        }
+        })
     }
 
     fn number_of_xmas_at_index(&self, index: usize) -> i32 {
@@ -180,6 +255,61 @@ impl<'a> XMASGrid<'a> {
         count_of_xmas
     }
 
+    // Builds one bit per byte of the grid, set where the byte equals `letter`,
+    // comparing 64 bytes at a time.
+    fn letter_bitboard(&self, letter: u8) -> Vec<u64> {
+        self.input_bytes
+            .chunks(64)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |bits, (bit, &byte)| {
+                    bits | (((byte == letter) as u64) << bit)
+                })
+            })
+            .collect()
+    }
+
+    // Counts `word` in all eight directions by and-ing letter bitboards shifted
+    // by the index offset of each step. The '\n' column never matches a letter,
+    // so shifted bits cannot wrap from one row into the next.
+    pub fn count_word_bitboard(&self, word: &[u8]) -> usize {
+        let row_stride = self.row_length + 1;
+        let offsets = [1, row_stride - 1, row_stride, row_stride + 1];
+        let reversed_word: Vec<u8> = word.iter().rev().copied().collect();
+        let mut count = 0;
+
+        for letters in [word, &reversed_word[..]] {
+            let bitboards: Vec<Vec<u64>> = letters
+                .iter()
+                .map(|&letter| self.letter_bitboard(letter))
+                .collect();
+
+            for offset in offsets {
+                for word_index in 0..bitboards.first().map_or(0, |bitboard| bitboard.len()) {
+                    let matches =
+                        bitboards
+                            .iter()
+                            .enumerate()
+                            .fold(u64::MAX, |bits, (step, bitboard)| {
+                                bits & shifted_bitboard_word(bitboard, word_index, step * offset)
+                            });
+
+                    count += matches.count_ones() as usize;
+                }
+            }
+        }
+
+        count
+    }
+
+    // Large grids go through the bitboard path, small ones through the scalar walk.
+    pub fn count_xmas_fast(&self) -> i32 {
+        if self.total_length < BITBOARD_MIN_LENGTH {
+            return self.count_xmas();
+        }
+
+        self.count_word_bitboard(b"XMAS") as i32
+    }
+
     fn number_of_x_mas_at_index(&self, index: usize) -> i32 {
         let top_left = match get_next_index_in_direction(
             self.row_length,
@@ -246,15 +376,131 @@ impl<'a> XMASGrid<'a> {
 
         count_of_x_mas
     }
+
+    fn coordinate(&self, index: usize) -> Coordinate {
+        Coordinate {
+            x: index % (self.row_length + 1),
+            y: index / (self.row_length + 1),
+        }
+    }
+
+    fn grid_match(&self, indexes: &[usize], direction: Option<Direction>) -> GridMatch {
+        GridMatch {
+            start_index: indexes[0],
+            direction,
+            cells: indexes
+                .iter()
+                .map(|&index| self.coordinate(index))
+                .collect(),
+        }
+    }
+
+    // Each XMAS is reported from its 'X' in the direction it reads.
+    pub fn find_xmas(&self) -> Vec<GridMatch> {
+        let mut matches: Vec<GridMatch> = Vec::new();
+
+        for (index, character) in self.input_bytes.iter().enumerate() {
+            if *character != b'S' {
+                continue;
+            }
+
+            for direction in DIRECTIONS {
+                let mut indexes = vec![index];
+
+                for expected in [b'A', b'M', b'X'] {
+                    match get_next_index_in_direction(
+                        self.row_length,
+                        self.total_length,
+                        indexes[indexes.len() - 1],
+                        &direction,
+                    ) {
+                        Some(next_index) if self.input_bytes[next_index] == expected => {
+                            indexes.push(next_index)
+                        }
+                        _ => break,
+                    }
+                }
+
+                if indexes.len() == 4 {
+                    indexes.reverse();
+                    matches.push(self.grid_match(
+                        &indexes,
+                        Some(Direction {
+                            x: -direction.x,
+                            y: -direction.y,
+                        }),
+                    ));
+                }
+            }
+        }
+
+        matches.sort_by_key(|grid_match| grid_match.start_index);
+
+        matches
+    }
+
+    // Each X-MAS is reported from its centre 'A', followed by its four corners.
+    pub fn find_x_mas(&self) -> Vec<GridMatch> {
+        let mut matches: Vec<GridMatch> = Vec::new();
+
+        for (index, character) in self.input_bytes.iter().enumerate() {
+            if *character != b'A' || self.number_of_x_mas_at_index(index) == 0 {
+                continue;
+            }
+
+            let mut indexes = vec![index];
+
+            for (x, y) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+                if let Some(corner) = get_next_index_in_direction(
+                    self.row_length,
+                    self.total_length,
+                    index,
+                    &Direction { x, y },
+                ) {
+                    indexes.push(corner);
+                }
+            }
+
+            matches.push(self.grid_match(&indexes, None));
+        }
+
+        matches
+    }
+
+    // Reproduces the puzzle's view where letters not involved in any match are
+    // replaced with '.'.
+    pub fn render_matches(&self, matches: &[GridMatch]) -> String {
+        let covered: HashSet<Coordinate> = matches
+            .iter()
+            .flat_map(|grid_match| grid_match.cells.iter().copied())
+            .collect();
+
+        self.input_bytes
+            .iter()
+            .enumerate()
+            .map(|(index, character)| match character {
+                b'\n' => '\n',
+                _ if covered.contains(&self.coordinate(index)) => *character as char,
+                _ => '.',
+            })
+            .collect()
+    }
 }
 
-#[derive(Debug)]
-struct Direction {
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GridMatch {
+    pub start_index: usize,
+    pub direction: Option<Direction>,
+    pub cells: Vec<Coordinate>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Direction {
     pub x: i32,
     pub y: i32,
 }
 
-const DIRECTIONS: [Direction; 8] = [
+pub const DIRECTIONS: [Direction; 8] = [
     Direction { x: -1, y: 1 },
     Direction { x: 0, y: 1 },
     Direction { x: 1, y: 1 },
@@ -273,6 +519,277 @@ enum State {
     FindX,
 }
 
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Coordinate {
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WordMatch {
+    pub word_index: usize,
+    pub start: Coordinate,
+    pub direction: Direction,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<char, usize>,
+    word_indexes: Vec<usize>,
+}
+
+// Prefix tree of the searched words so that each walk from a cell in a direction
+// follows every word sharing that prefix at once.
+struct Trie {
+    nodes: Vec<TrieNode>,
+}
+
+impl Trie {
+    fn new(words: &[&str]) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+
+        for (word_index, word) in words.iter().enumerate() {
+            if word.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+
+            for character in word.chars() {
+                node = match nodes[node].children.get(&character) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(character, child);
+                        child
+                    }
+                };
+            }
+
+            nodes[node].word_indexes.push(word_index);
+        }
+
+        Self { nodes }
+    }
+}
+
+pub struct WordSearch {
+    rows: Vec<Vec<char>>,
+}
+
+impl WordSearch {
+    pub fn new(input: &str) -> Self {
+        match Self::parse(input, false) {
+            Ok(word_search) => word_search,
+            Err(error) => panic!("{}", error),
+        }
+    }
+
+    // Parses the grid like `XMASGrid::parse`. Ragged rows are kept as they are
+    // when `allow_ragged_rows` is set, and their missing cells match nothing.
+    pub fn parse(input: &str, allow_ragged_rows: bool) -> Result<Self, String> {
+        let rows = grid_rows(input)?;
+
+        if !allow_ragged_rows {
+            check_row_lengths(rows.iter().map(|row| row.chars().count()))?;
+        }
+
+        Ok(Self {
+            rows: rows.iter().map(|row| row.chars().collect()).collect(),
+        })
+    }
+
+    fn cell(&self, x: i64, y: i64) -> Option<char> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+
+        self.rows
+            .get(y as usize)
+            .and_then(|row| row.get(x as usize))
+            .copied()
+    }
+
+    // Every word is reported once per allowed direction it can be read in, so a
+    // palindrome or a single letter shows up several times from the same cell.
+    pub fn find_words(&self, words: &[&str], directions: &[Direction]) -> Vec<WordMatch> {
+        let trie = Trie::new(words);
+        let mut matches: Vec<WordMatch> = Vec::new();
+
+        for (y, row) in self.rows.iter().enumerate() {
+            for x in 0..row.len() {
+                for direction in directions {
+                    let mut node = 0;
+                    let mut current_x = x as i64;
+                    let mut current_y = y as i64;
+
+                    while let Some(character) = self.cell(current_x, current_y) {
+                        node = match trie.nodes[node].children.get(&character) {
+                            Some(&child) => child,
+                            None => break,
+                        };
+
+                        for &word_index in trie.nodes[node].word_indexes.iter() {
+                            matches.push(WordMatch {
+                                word_index,
+                                start: Coordinate { x, y },
+                                direction: *direction,
+                            });
+                        }
+
+                        current_x += direction.x as i64;
+                        current_y += direction.y as i64;
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    pub fn count_words(&self, words: &[&str], directions: &[Direction]) -> usize {
+        self.find_words(words, directions).len()
+    }
+
+    fn shape_matches_at(&self, pattern: &ShapePattern, x: usize, y: usize) -> bool {
+        pattern.cells.iter().enumerate().all(|(dy, row)| {
+            row.iter().enumerate().all(|(dx, expected)| {
+                match self.cell((x + dx) as i64, (y + dy) as i64) {
+                    Some(character) => expected.is_none_or(|expected| expected == character),
+                    None => false,
+                }
+            })
+        })
+    }
+
+    pub fn find_shape(&self, pattern: &ShapePattern, all_orientations: bool) -> Vec<ShapeMatch> {
+        let orientations = match all_orientations {
+            true => pattern.orientations(),
+            false => vec![(0, false, pattern.clone())],
+        };
+        let mut matches: Vec<ShapeMatch> = Vec::new();
+
+        for (y, row) in self.rows.iter().enumerate() {
+            for x in 0..row.len() {
+                for (quarter_turns, reflected, oriented_pattern) in orientations.iter() {
+                    if self.shape_matches_at(oriented_pattern, x, y) {
+                        matches.push(ShapeMatch {
+                            origin: Coordinate { x, y },
+                            quarter_turns: *quarter_turns,
+                            reflected: *reflected,
+                        });
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    pub fn count_shape(&self, pattern: &ShapePattern, all_orientations: bool) -> usize {
+        self.find_shape(pattern, all_orientations).len()
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ShapePattern {
+    cells: Vec<Vec<Option<char>>>,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ShapeMatch {
+    pub origin: Coordinate,
+    pub quarter_turns: u8,
+    pub reflected: bool,
+}
+
+impl ShapePattern {
+    // Templates are written like the puzzle diagrams, with '.' matching any letter.
+    pub fn new(template: &str) -> Self {
+        let mut cells: Vec<Vec<Option<char>>> = grid_rows(template)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|line| {
+                line.chars()
+                    .map(|character| match character {
+                        '.' => None,
+                        _ => Some(character),
+                    })
+                    .collect()
+            })
+            .collect();
+        let width = cells.iter().map(|row| row.len()).max().unwrap_or(0);
+
+        for row in cells.iter_mut() {
+            row.resize(width, None);
+        }
+
+        Self { cells }
+    }
+
+    fn rotated(&self) -> Self {
+        let height = self.cells.len();
+        let width = self.cells.first().map_or(0, |row| row.len());
+
+        Self {
+            cells: (0..width)
+                .map(|x| (0..height).rev().map(|y| self.cells[y][x]).collect())
+                .collect(),
+        }
+    }
+
+    fn reflected(&self) -> Self {
+        Self {
+            cells: self
+                .cells
+                .iter()
+                .map(|row| row.iter().rev().copied().collect())
+                .collect(),
+        }
+    }
+
+    // Distinct orientations of the pattern, each with the number of clockwise
+    // quarter turns and whether it was mirrored first.
+    fn orientations(&self) -> Vec<(u8, bool, ShapePattern)> {
+        let mut orientations: Vec<(u8, bool, ShapePattern)> = Vec::new();
+
+        for reflected in [false, true] {
+            let mut pattern = match reflected {
+                false => self.clone(),
+                true => self.reflected(),
+            };
+
+            for quarter_turns in 0..4 {
+                if !orientations.iter().any(|(_, _, other)| *other == pattern) {
+                    orientations.push((quarter_turns, reflected, pattern.clone()));
+                }
+
+                pattern = pattern.rotated();
+            }
+        }
+
+        orientations
+    }
+}
+
+const BITBOARD_MIN_LENGTH: usize = 4096;
+
+// Returns the 64 bits of `bitboard` starting at bit `word_index * 64 + shift`.
+fn shifted_bitboard_word(bitboard: &[u64], word_index: usize, shift: usize) -> u64 {
+    let source_index = word_index + shift / 64;
+    let bit_shift = shift % 64;
+    let low = bitboard.get(source_index).copied().unwrap_or(0);
+
+    if bit_shift == 0 {
+        return low;
+    }
+
+    let high = bitboard.get(source_index + 1).copied().unwrap_or(0);
+
+    (low >> bit_shift) | (high << (64 - bit_shift))
+}
+
 fn is_index_valid(index: usize, row_length: usize, total_length: usize) -> bool {
     index < total_length && (index % (row_length + 1) != row_length)
 }
@@ -324,18 +841,19 @@ fn update_state(current_state: &mut State, character: &u8) -> bool {
 }
 
 pub fn part1(input: &str) -> Result<String, String> {
-    let xmas_grid = XMASGrid::new(input);
-    Ok(xmas_grid.count_xmas().to_string())
+    let xmas_grid = XMASGrid::parse(input, false)?;
+    Ok(xmas_grid.count_xmas_fast().to_string())
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
-    let xmas_grid = XMASGrid::new(input);
+    let xmas_grid = XMASGrid::parse(input, false)?;
     Ok(xmas_grid.count_x_mas().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::Xorshift;
 
     static TEST_INPUT: &str = "\
 MMMSXXMASM
@@ -393,6 +911,179 @@ MXMXAXMASX
         assert_eq!(xmas_grid.number_of_xmas_at_index(17), 1);
     }
 
+    #[test]
+    fn test_word_search() {
+        let word_search = WordSearch::new(TEST_INPUT);
+
+        assert_eq!(word_search.count_words(&["XMAS"], &DIRECTIONS), 18);
+        assert_eq!(
+            word_search.count_words(&["XMAS"], &[Direction { x: 1, y: 0 }]),
+            3
+        );
+
+        let matches = word_search.find_words(&["XMAS", "MAS", "ZZZ"], &DIRECTIONS);
+        assert!(matches.contains(&WordMatch {
+            word_index: 0,
+            start: Coordinate { x: 5, y: 0 },
+            direction: Direction { x: 1, y: 0 },
+        }));
+        assert!(matches.iter().all(|word_match| word_match.word_index != 2));
+
+        let unicode_search = WordSearch::new("ÉTÉ\nTÉT\n");
+        assert_eq!(unicode_search.count_words(&["ÉTÉ"], &DIRECTIONS), 2);
+    }
+
+    #[test]
+    fn test_find_shape() {
+        let word_search = WordSearch::new(TEST_INPUT);
+        let x_mas = ShapePattern::new("M.S\n.A.\nM.S\n");
+
+        assert_eq!(word_search.count_shape(&x_mas, true), 9);
+        assert_eq!(word_search.count_shape(&x_mas, false), 2);
+        assert_eq!(x_mas.orientations().len(), 4);
+
+        let matches = word_search.find_shape(&ShapePattern::new("XMAS"), false);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].origin, Coordinate { x: 5, y: 0 });
+    }
+
+    #[test]
+    fn test_render_matches() {
+        let xmas_grid = XMASGrid::new(TEST_INPUT);
+
+        let xmas = xmas_grid.find_xmas();
+        assert_eq!(xmas.len(), 18);
+        assert_eq!(
+            xmas[0],
+            GridMatch {
+                start_index: 4,
+                direction: Some(Direction { x: 1, y: 1 }),
+                cells: vec![
+                    Coordinate { x: 4, y: 0 },
+                    Coordinate { x: 5, y: 1 },
+                    Coordinate { x: 6, y: 2 },
+                    Coordinate { x: 7, y: 3 },
+                ],
+            }
+        );
+        assert_eq!(
+            xmas_grid.render_matches(&xmas),
+            "\
+....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX
+"
+        );
+
+        let x_mas = xmas_grid.find_x_mas();
+        assert_eq!(x_mas.len(), 9);
+        assert_eq!(
+            xmas_grid.render_matches(&x_mas),
+            "\
+.M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+..........
+"
+        );
+    }
+
+    #[test]
+    fn test_parse_line_endings_and_ragged_rows() {
+        let crlf_input = TEST_INPUT.replace('\n', "\r\n");
+        assert_eq!(part1(&crlf_input), Ok(18.to_string()));
+        assert_eq!(part2(&crlf_input), Ok(9.to_string()));
+
+        assert_eq!(part1(TEST_INPUT.trim_end()), Ok(18.to_string()));
+        assert_eq!(part1("XMAS"), Ok(1.to_string()));
+
+        assert_eq!(
+            part1("XMAS\nXMA\nXMAS\n"),
+            Err("Row 2 has 3 characters, expected 4".to_string())
+        );
+        assert_eq!(part1(""), Err("Unable to determine row length".to_string()));
+
+        let padded_grid = XMASGrid::parse("XMAS\nMA\nA\nS\n", true).unwrap();
+        assert_eq!(&padded_grid.input_bytes[..], b"XMAS\nMA..\nA...\nS...\n");
+        assert_eq!(padded_grid.count_xmas(), 2);
+
+        let word_search = WordSearch::new(&crlf_input);
+        assert_eq!(word_search.count_words(&["XMAS"], &DIRECTIONS), 18);
+        assert_eq!(
+            word_search.count_shape(&ShapePattern::new("M.S\r\n.A.\r\nM.S\r\n"), false),
+            2
+        );
+        assert_eq!(word_search.count_shape(&ShapePattern::new("S."), false), 18);
+        assert_eq!(
+            WordSearch::parse("XMAS\nXMA\n", false).err(),
+            Some("Row 2 has 3 characters, expected 4".to_string())
+        );
+        assert_eq!(
+            WordSearch::parse("XMAS\nXMA\n", true)
+                .unwrap()
+                .count_words(&["XMA"], &DIRECTIONS),
+            2
+        );
+    }
+
+    #[test]
+    fn test_count_word_bitboard() {
+        let xmas_grid = XMASGrid::new(TEST_INPUT);
+
+        assert_eq!(xmas_grid.count_word_bitboard(b"XMAS"), 18);
+
+        let large_input = TEST_INPUT.replace('\n', "").repeat(60);
+        let large_input: String = large_input
+            .as_bytes()
+            .chunks(100)
+            .map(|row| format!("{}\n", std::str::from_utf8(row).unwrap()))
+            .collect();
+        let large_grid = XMASGrid::new(&large_input);
+
+        assert_eq!(large_grid.count_xmas_fast(), large_grid.count_xmas());
+    }
+
+    #[test]
+    fn test_count_word_bitboard_matches_scalar_on_random_grids() {
+        let mut random = Xorshift::new(0x2024_1204);
+
+        for _ in 0..200 {
+            let rows = 1 + random.below(40) as usize;
+            let columns = 1 + random.below(140) as usize;
+            let mut input = String::new();
+
+            for _ in 0..rows {
+                for _ in 0..columns {
+                    input.push(b"XMASZ"[random.below(5) as usize] as char);
+                }
+
+                input.push('\n');
+            }
+
+            let xmas_grid = XMASGrid::new(&input);
+
+            assert_eq!(
+                xmas_grid.count_word_bitboard(b"XMAS") as i32,
+                xmas_grid.count_xmas(),
+                "{}",
+                input
+            );
+        }
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(part1(TEST_INPUT), Ok(18.to_string()));
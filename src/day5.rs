@@ -91,82 +91,683 @@ After taking only the incorrectly-ordered updates and ordering them correctly, t
 Find the updates which are not in the correct order. What do you get if you add up the middle page numbers after correctly ordering just those updates?
 */
 
-fn non_empty_lines(input: &str) -> impl Iterator<Item = &str> {
-    input.split('\n').filter(|line| !line.is_empty())
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+fn non_empty_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(|(line_index, line)| (line_index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
 }
 
-fn solve(input: &str, fixed_only: bool) -> i32 {
-    let mut middle_page_numbers_sum = 0;
-    let mut page_ordering_rules: Vec<Vec<&str>> = Vec::new();
-
-    for (line_number, line) in non_empty_lines(input).enumerate() {
-        match line.find(',') {
-            Some(_) => {
-                let mut update = line.split(',').collect::<Vec<&str>>();
-                let mut fixed = false;
-                let mut fixed_during_last_pass = false;
-
-                loop {
-                    for rule in page_ordering_rules.iter() {
-                        let page_ordering_rule_first: Option<usize> =
-                            update.iter().position(|&item| item == rule[0]);
-                        let page_ordering_rule_last: Option<usize> =
-                            update.iter().position(|&item| item == rule[1]);
-
-                        match (page_ordering_rule_first, page_ordering_rule_last) {
-                            (Some(first), Some(last)) => {
-                                if first > last {
-                                    update.remove(first);
-                                    update.insert(last, rule[0]);
-                                    fixed = true;
-                                    fixed_during_last_pass = true;
-                                }
-                            }
-                            (Some(_first), None) => {}
-                            (None, Some(_last)) => {}
-                            (None, None) => {}
-                        }
-                    }
+fn parse_page(page: &str, line_number: usize) -> Result<i32, String> {
+    match page.trim().parse::<i32>() {
+        Ok(page) => Ok(page),
+        Err(_) => Err(format!(
+            "Could not parse page number {:?} on line {}",
+            page, line_number
+        )),
+    }
+}
+
+pub struct PrintQueue {
+    pub rules: Vec<(i32, i32)>,
+    pub updates: Vec<Vec<i32>>,
+}
+
+impl PrintQueue {
+    pub fn new(input: &str) -> Result<Self, String> {
+        let mut rules: Vec<(i32, i32)> = Vec::new();
+        let mut updates: Vec<Vec<i32>> = Vec::new();
+
+        for (line_number, line) in non_empty_lines(input) {
+            match line.split_once('|') {
+                Some((before, after)) => rules.push((
+                    parse_page(before, line_number)?,
+                    parse_page(after, line_number)?,
+                )),
+                None => updates.push(
+                    line.split(',')
+                        .map(|page| parse_page(page, line_number))
+                        .collect::<Result<Vec<i32>, String>>()?,
+                ),
+            }
+        }
+
+        Ok(Self { rules, updates })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CyclicRules {
+    pub cycle: Vec<i32>,
+}
+
+impl std::fmt::Display for CyclicRules {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let pages: Vec<String> = self.cycle.iter().map(|page| page.to_string()).collect();
+
+        write!(
+            f,
+            "Page ordering rules form a cycle: {}",
+            pages.join(" -> ")
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+// The page ordering rules as a directed graph, from each page to the pages that
+// have to be printed after it.
+pub struct RuleGraph {
+    successors: HashMap<i32, HashSet<i32>>,
+}
+
+impl RuleGraph {
+    pub fn new(rules: &[(i32, i32)]) -> Self {
+        let mut successors: HashMap<i32, HashSet<i32>> = HashMap::new();
+
+        for (before, after) in rules.iter() {
+            successors.entry(*before).or_default().insert(*after);
+        }
 
-                    if (fixed && !fixed_only) || (!fixed && fixed_only) {
-                        break;
+        Self { successors }
+    }
+
+    pub fn must_precede(&self, before: i32, after: i32) -> bool {
+        self.successors
+            .get(&before)
+            .is_some_and(|successors| successors.contains(&after))
+    }
+
+    fn sorted_successors(&self, page: i32, pages: &HashSet<i32>) -> Vec<i32> {
+        let mut successors: Vec<i32> = self
+            .successors
+            .get(&page)
+            .map(|successors| {
+                successors
+                    .iter()
+                    .filter(|successor| pages.contains(successor))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        successors.sort();
+        successors
+    }
+
+    fn visit(
+        &self,
+        page: i32,
+        pages: &HashSet<i32>,
+        visit_states: &mut HashMap<i32, VisitState>,
+        path: &mut Vec<i32>,
+    ) -> Option<Vec<i32>> {
+        visit_states.insert(page, VisitState::InProgress);
+        path.push(page);
+
+        for successor in self.sorted_successors(page, pages) {
+            match visit_states.get(&successor) {
+                Some(VisitState::InProgress) => {
+                    let start = path.iter().position(|&other| other == successor)?;
+                    let mut cycle = path[start..].to_vec();
+                    cycle.push(successor);
+
+                    return Some(cycle);
+                }
+                Some(VisitState::Done) => {}
+                None => {
+                    if let Some(cycle) = self.visit(successor, pages, visit_states, path) {
+                        return Some(cycle);
                     }
+                }
+            }
+        }
+
+        path.pop();
+        visit_states.insert(page, VisitState::Done);
+
+        None
+    }
+
+    // Looks for a cycle using only the rules between the given pages. The cycle
+    // starts and ends with the same page.
+    pub fn find_cycle(&self, pages: &[i32]) -> Option<Vec<i32>> {
+        let page_set: HashSet<i32> = pages.iter().copied().collect();
+        let mut sorted_pages: Vec<i32> = page_set.iter().copied().collect();
+        let mut visit_states: HashMap<i32, VisitState> = HashMap::new();
+
+        sorted_pages.sort();
+
+        for page in sorted_pages {
+            if visit_states.contains_key(&page) {
+                continue;
+            }
+
+            if let Some(cycle) = self.visit(page, &page_set, &mut visit_states, &mut Vec::new()) {
+                return Some(cycle);
+            }
+        }
+
+        None
+    }
+
+    // Checks the whole rule set. Puzzle inputs usually contain cycles that never
+    // show up within a single update, so this is stricter than what part 1 and
+    // part 2 need.
+    pub fn validate(&self) -> Result<(), CyclicRules> {
+        let pages: Vec<i32> = self
+            .successors
+            .iter()
+            .flat_map(|(page, successors)| std::iter::once(page).chain(successors.iter()))
+            .copied()
+            .collect();
+
+        match self.find_cycle(&pages) {
+            Some(cycle) => Err(CyclicRules { cycle }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn validate_update(&self, update: &[i32]) -> Result<(), CyclicRules> {
+        match self.find_cycle(update) {
+            Some(cycle) => Err(CyclicRules { cycle }),
+            None => Ok(()),
+        }
+    }
+
+    pub fn is_ordered(&self, update: &[i32]) -> bool {
+        update.iter().enumerate().all(|(index, page)| {
+            update[..=index]
+                .iter()
+                .all(|earlier_page| !self.must_precede(*page, *earlier_page))
+        })
+    }
 
-                    if (!fixed && !fixed_only) || (fixed_only && fixed && !fixed_during_last_pass) {
-                        match update[update.len() / 2].parse::<i32>() {
-                            Ok(parsed) => middle_page_numbers_sum += parsed,
-                            Err(_) => {
-                                panic!("Could not parse number of update on line {:?}", line_number)
-                            }
-                        };
+    // Topological sort of the pages of the update, where `precedes` tells
+    // whether the page at one index has to come before the page at another.
+    // Among the pages that are free to go next, the one that comes first in the
+    // update is picked. The result holds the indices of the pages in the update.
+    fn order_indices_by(
+        &self,
+        update: &[i32],
+        precedes: impl Fn(usize, usize) -> bool,
+    ) -> Result<Vec<usize>, CyclicRules> {
+        let mut predecessor_counts: Vec<usize> = vec![0; update.len()];
 
-                        break;
+        for (index, predecessor_count) in predecessor_counts.iter_mut().enumerate() {
+            *predecessor_count = (0..update.len())
+                .filter(|&other_index| precedes(other_index, index))
+                .count();
+        }
+
+        let mut ready: BTreeSet<usize> = (0..update.len())
+            .filter(|&index| predecessor_counts[index] == 0)
+            .collect();
+        let mut ordered_indices: Vec<usize> = Vec::with_capacity(update.len());
+
+        while let Some(index) = ready.pop_first() {
+            ordered_indices.push(index);
+
+            for (other_index, predecessor_count) in predecessor_counts.iter_mut().enumerate() {
+                if precedes(index, other_index) {
+                    *predecessor_count -= 1;
+
+                    if *predecessor_count == 0 {
+                        ready.insert(other_index);
                     }
+                }
+            }
+        }
+
+        if ordered_indices.len() < update.len() {
+            self.validate_update(update)?;
+        }
+
+        Ok(ordered_indices)
+    }
+
+    // Uses only the rules between the pages of the update, so an ordered update
+    // is returned unchanged.
+    fn order_indices(&self, update: &[i32]) -> Result<Vec<usize>, CyclicRules> {
+        self.order_indices_by(update, |index, other_index| {
+            self.must_precede(update[index], update[other_index])
+        })
+    }
+
+    pub fn order(&self, update: &[i32]) -> Result<Vec<i32>, CyclicRules> {
+        Ok(self
+            .order_indices(update)?
+            .into_iter()
+            .map(|index| update[index])
+            .collect())
+    }
+
+    // Every pair of pages printed in the wrong order, in the order they appear
+    // in the update.
+    pub fn violations(&self, update: &[i32]) -> Vec<RuleViolation> {
+        let mut violations: Vec<RuleViolation> = Vec::new();
+
+        for (after_position, after) in update.iter().enumerate() {
+            for (before_position, before) in update.iter().enumerate().skip(after_position + 1) {
+                if self.must_precede(*before, *after) {
+                    violations.push(RuleViolation {
+                        before: *before,
+                        after: *after,
+                        before_position,
+                        after_position,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    // Marks the largest set of pages that can stay where they are while the
+    // others are moved. Two pages can both stay unless the rules, followed
+    // through any other pages of the update, put the later one first. That
+    // relation is a partial order, and the largest set of pages without such a
+    // pair is its largest antichain, read off a maximum matching by Dilworth's
+    // and König's theorems.
+    fn pages_in_place(&self, update: &[i32]) -> Vec<bool> {
+        let page_set: HashSet<i32> = update.iter().copied().collect();
+        let reachable: Vec<HashSet<i32>> = update
+            .iter()
+            .map(|page| self.reachable(*page, &page_set))
+            .collect();
+        let inverted_with: Vec<Vec<usize>> = (0..update.len())
+            .map(|index| {
+                ((index + 1)..update.len())
+                    .filter(|&later_index| reachable[later_index].contains(&update[index]))
+                    .collect()
+            })
+            .collect();
+        let mut matched_with: Vec<Option<usize>> = vec![None; update.len()];
+
+        for index in 0..update.len() {
+            augment_matching(
+                index,
+                &inverted_with,
+                &mut matched_with,
+                &mut vec![false; update.len()],
+            );
+        }
+
+        let mut reached_earlier: Vec<bool> = (0..update.len())
+            .map(|index| !matched_with.contains(&Some(index)))
+            .collect();
+        let mut reached_later: Vec<bool> = vec![false; update.len()];
+        let mut pending: Vec<usize> = (0..update.len())
+            .filter(|&index| reached_earlier[index])
+            .collect();
 
-                    if fixed_only && fixed_during_last_pass {
-                        fixed_during_last_pass = false;
-                        continue;
+        while let Some(index) = pending.pop() {
+            for &later_index in inverted_with[index].iter() {
+                if reached_later[later_index] {
+                    continue;
+                }
+
+                reached_later[later_index] = true;
+
+                if let Some(matched_index) = matched_with[later_index] {
+                    if !reached_earlier[matched_index] {
+                        reached_earlier[matched_index] = true;
+                        pending.push(matched_index);
                     }
                 }
             }
-            None => page_ordering_rules.push(line.split('|').collect::<Vec<&str>>()),
         }
+
+        (0..update.len())
+            .map(|index| reached_earlier[index] && !reached_later[index])
+            .collect()
     }
 
-    middle_page_numbers_sum
+    // Keeps as many pages in place as possible. The corrected update is the
+    // topological sort that also keeps those pages in their original order, and
+    // every other page is moved, in corrected order, to just after the page that
+    // precedes it there.
+    pub fn explain(&self, update: &[i32]) -> Result<UpdateExplanation, CyclicRules> {
+        self.validate_update(update)?;
+
+        let in_place = self.pages_in_place(update);
+        let ordered_indices = self.order_indices_by(update, |index, other_index| {
+            self.must_precede(update[index], update[other_index])
+                || (in_place[index] && in_place[other_index] && index < other_index)
+        })?;
+        let mut current_indices: Vec<usize> = (0..update.len()).collect();
+        let mut moves: Vec<PageMove> = Vec::new();
+
+        for (corrected_position, &index) in ordered_indices.iter().enumerate() {
+            if in_place[index] {
+                continue;
+            }
+
+            let from = current_indices
+                .iter()
+                .position(|&current_index| current_index == index)
+                .unwrap_or_default();
+            current_indices.remove(from);
+
+            let to = match corrected_position {
+                0 => 0,
+                _ => {
+                    let previous_index = ordered_indices[corrected_position - 1];
+
+                    current_indices
+                        .iter()
+                        .position(|&current_index| current_index == previous_index)
+                        .map_or(0, |position| position + 1)
+                }
+            };
+            current_indices.insert(to, index);
+
+            moves.push(PageMove {
+                page: update[index],
+                from,
+                to,
+            });
+        }
+
+        Ok(UpdateExplanation {
+            update: update.to_vec(),
+            corrected: ordered_indices.iter().map(|&index| update[index]).collect(),
+            violations: self.violations(update),
+            moves,
+        })
+    }
+
+    fn reachable(&self, page: i32, pages: &HashSet<i32>) -> HashSet<i32> {
+        let mut reached: HashSet<i32> = HashSet::new();
+        let mut pending: Vec<i32> = vec![page];
+
+        while let Some(current) = pending.pop() {
+            for successor in self.sorted_successors(current, pages) {
+                if reached.insert(successor) {
+                    pending.push(successor);
+                }
+            }
+        }
+
+        reached
+    }
+
+    // Writes the rules between the given pages, or between all pages, as a
+    // Graphviz digraph. Pages that reach each other through the rules form a
+    // component, whose rules are all kept. A rule between two components is
+    // left out when another component lies on a path between them, which is
+    // the transitive reduction of the graph of components. Rules broken by the
+    // update are always drawn, in red.
+    pub fn to_dot(&self, name: &str, update: Option<&[i32]>) -> String {
+        let page_set: HashSet<i32> = match update {
+            Some(update) => update.iter().copied().collect(),
+            None => self
+                .successors
+                .iter()
+                .flat_map(|(page, successors)| std::iter::once(page).chain(successors.iter()))
+                .copied()
+                .collect(),
+        };
+        let mut pages: Vec<i32> = page_set.iter().copied().collect();
+        let violated: HashSet<(i32, i32)> = update
+            .map(|update| {
+                self.violations(update)
+                    .iter()
+                    .map(|violation| (violation.before, violation.after))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let reachable: HashMap<i32, HashSet<i32>> = pages
+            .iter()
+            .map(|page| (*page, self.reachable(*page, &page_set)))
+            .collect();
+        // Each component is named after its lowest page.
+        let components: HashMap<i32, i32> = pages
+            .iter()
+            .map(|page| {
+                let component = reachable[page]
+                    .iter()
+                    .filter(|other_page| reachable[*other_page].contains(page))
+                    .fold(*page, |lowest, other_page| lowest.min(*other_page));
+
+                (*page, component)
+            })
+            .collect();
+        let mut dot = format!("digraph {} {{\n", name);
+
+        pages.sort();
+
+        if let Some(cycle) = self.find_cycle(&pages) {
+            dot.push_str(&format!("    // {}\n", CyclicRules { cycle }));
+        }
+
+        for page in pages.iter() {
+            dot.push_str(&format!("    {};\n", page));
+        }
+
+        for page in pages.iter() {
+            let component = components[page];
+            // Pages reached by leaving this component through another one.
+            let reached_through_others: HashSet<i32> = pages
+                .iter()
+                .filter(|other_page| components[*other_page] == component)
+                .flat_map(|other_page| self.sorted_successors(*other_page, &page_set))
+                .filter(|successor| components[successor] != component)
+                .flat_map(|successor| reachable[&successor].iter().copied())
+                .filter(|reached_page| components[reached_page] != component)
+                .collect();
+
+            for successor in self.sorted_successors(*page, &page_set) {
+                if violated.contains(&(*page, successor)) {
+                    dot.push_str(&format!("    {} -> {} [color=red];\n", page, successor));
+                } else if components[&successor] == component
+                    || !reached_through_others.contains(&successor)
+                {
+                    dot.push_str(&format!("    {} -> {};\n", page, successor));
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+// A rule `before|after` broken by an update, with the positions of both pages.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RuleViolation {
+    pub before: i32,
+    pub after: i32,
+    pub before_position: usize,
+    pub after_position: usize,
+}
+
+// Taking a page out of the update and inserting it again. Both positions are
+// counted in the update as it is when the move is made, after the moves before
+// it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PageMove {
+    pub page: i32,
+    pub from: usize,
+    pub to: usize,
+}
+
+// Why an update is out of order and how to fix it. No valid order can be
+// reached with fewer moves, and making them one after another gives the
+// corrected update.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UpdateExplanation {
+    pub update: Vec<i32>,
+    pub corrected: Vec<i32>,
+    pub violations: Vec<RuleViolation>,
+    pub moves: Vec<PageMove>,
+}
+
+impl UpdateExplanation {
+    pub fn is_ordered(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+// Tries to match the page at `index` with a later page it is inverted with,
+// moving earlier matches along if needed.
+fn augment_matching(
+    index: usize,
+    inverted_with: &[Vec<usize>],
+    matched_with: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &later_index in inverted_with[index].iter() {
+        if visited[later_index] {
+            continue;
+        }
+
+        visited[later_index] = true;
+
+        let augmented = match matched_with[later_index] {
+            Some(matched_index) => {
+                augment_matching(matched_index, inverted_with, matched_with, visited)
+            }
+            None => true,
+        };
+
+        if augmented {
+            matched_with[later_index] = Some(index);
+            return true;
+        }
+    }
+
+    false
+}
+
+fn join_pages(pages: &[i32]) -> String {
+    pages
+        .iter()
+        .map(|page| page.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+// Positions are printed starting from 1.
+pub fn explain_updates(input: &str) -> Result<String, String> {
+    let print_queue = PrintQueue::new(input)?;
+    let rule_graph = RuleGraph::new(&print_queue.rules);
+    let mut explanations = String::new();
+
+    for (update_number, update) in print_queue.updates.iter().enumerate() {
+        let explanation = rule_graph
+            .explain(update)
+            .map_err(|error| error.to_string())?;
+
+        if !explanations.is_empty() {
+            explanations.push('\n');
+        }
+
+        explanations.push_str(&format!(
+            "Update {}: {}\n",
+            update_number + 1,
+            join_pages(&explanation.update)
+        ));
+
+        if explanation.is_ordered() {
+            explanations.push_str("  in the right order\n");
+            continue;
+        }
+
+        for violation in explanation.violations.iter() {
+            explanations.push_str(&format!(
+                "  broken rule {}|{}: {} at position {}, {} at position {}\n",
+                violation.before,
+                violation.after,
+                violation.before,
+                violation.before_position + 1,
+                violation.after,
+                violation.after_position + 1
+            ));
+        }
+
+        for page_move in explanation.moves.iter() {
+            explanations.push_str(&format!(
+                "  move {} from position {} to position {}\n",
+                page_move.page,
+                page_move.from + 1,
+                page_move.to + 1
+            ));
+        }
+
+        explanations.push_str(&format!(
+            "  original:  {}\n  corrected: {}\n",
+            join_pages(&explanation.update),
+            join_pages(&explanation.corrected)
+        ));
+    }
+
+    Ok(explanations)
+}
+
+pub fn rules_dot(input: &str) -> Result<String, String> {
+    let print_queue = PrintQueue::new(input)?;
+
+    Ok(RuleGraph::new(&print_queue.rules).to_dot("rules", None))
+}
+
+// One digraph per update, which `dot` renders as separate pages.
+pub fn updates_dot(input: &str) -> Result<String, String> {
+    let print_queue = PrintQueue::new(input)?;
+    let rule_graph = RuleGraph::new(&print_queue.rules);
+
+    Ok(print_queue
+        .updates
+        .iter()
+        .enumerate()
+        .map(|(update_index, update)| {
+            rule_graph.to_dot(&format!("update_{}", update_index + 1), Some(update))
+        })
+        .collect())
+}
+
+fn solve(input: &str, fixed_only: bool) -> Result<i32, String> {
+    let print_queue = PrintQueue::new(input)?;
+    let rule_graph = RuleGraph::new(&print_queue.rules);
+    let mut middle_page_numbers_sum = 0;
+
+    // An update whose pages form a cycle is never in order, so only fixing it
+    // runs into the cycle.
+    for update in print_queue.updates.iter() {
+        match (rule_graph.is_ordered(update), fixed_only) {
+            (true, false) => middle_page_numbers_sum += update[update.len() / 2],
+            (false, true) => {
+                let ordered_update = rule_graph
+                    .order(update)
+                    .map_err(|error| error.to_string())?;
+                middle_page_numbers_sum += ordered_update[ordered_update.len() / 2];
+            }
+            _ => {}
+        }
+    }
+
+    Ok(middle_page_numbers_sum)
 }
 
 pub fn part1(input: &str) -> Result<String, String> {
-    Ok(solve(input, false).to_string())
+    Ok(solve(input, false)?.to_string())
 }
 
 pub fn part2(input: &str) -> Result<String, String> {
-    Ok(solve(input, true).to_string())
+    Ok(solve(input, true)?.to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_utils::Xorshift;
 
     static TEST_INPUT: &str = "\
 47|53
@@ -208,4 +809,284 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(123.to_string()));
     }
+
+    #[test]
+    fn test_cyclic_rules() {
+        let cyclic_input = "1|2\n2|3\n3|1\n4|1\n\n1,2,4\n3,1,2\n";
+        let print_queue = PrintQueue::new(cyclic_input).unwrap();
+        let rule_graph = RuleGraph::new(&print_queue.rules);
+
+        assert_eq!(
+            rule_graph.validate(),
+            Err(CyclicRules {
+                cycle: vec![1, 2, 3, 1]
+            })
+        );
+        assert_eq!(rule_graph.validate_update(&[1, 2, 4]), Ok(()));
+        assert_eq!(
+            rule_graph.order(&[3, 1, 2]),
+            Err(CyclicRules {
+                cycle: vec![1, 2, 3, 1]
+            })
+        );
+        assert_eq!(
+            part2(cyclic_input),
+            Err("Page ordering rules form a cycle: 1 -> 2 -> 3 -> 1".to_string())
+        );
+        assert_eq!(
+            part2("5|5\n\n5,6\n"),
+            Err("Page ordering rules form a cycle: 5 -> 5".to_string())
+        );
+        assert_eq!(part1("1|2\n2|3\n3|1\n\n4,5\n3,1,2\n"), Ok(5.to_string()));
+        assert_eq!(part1("5|5\n\n5,6\n"), Ok(0.to_string()));
+
+        let print_queue = PrintQueue::new(TEST_INPUT).unwrap();
+        assert_eq!(RuleGraph::new(&print_queue.rules).validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_explain() {
+        let print_queue = PrintQueue::new(TEST_INPUT).unwrap();
+        let rule_graph = RuleGraph::new(&print_queue.rules);
+
+        assert!(rule_graph
+            .explain(&[75, 47, 61, 53, 29])
+            .unwrap()
+            .is_ordered());
+        assert_eq!(
+            rule_graph.explain(&[97, 13, 75, 29, 47]),
+            Ok(UpdateExplanation {
+                update: vec![97, 13, 75, 29, 47],
+                corrected: vec![97, 75, 47, 29, 13],
+                violations: vec![
+                    RuleViolation {
+                        before: 75,
+                        after: 13,
+                        before_position: 2,
+                        after_position: 1
+                    },
+                    RuleViolation {
+                        before: 29,
+                        after: 13,
+                        before_position: 3,
+                        after_position: 1
+                    },
+                    RuleViolation {
+                        before: 47,
+                        after: 13,
+                        before_position: 4,
+                        after_position: 1
+                    },
+                    RuleViolation {
+                        before: 47,
+                        after: 29,
+                        before_position: 4,
+                        after_position: 3
+                    },
+                ],
+                moves: vec![
+                    PageMove {
+                        page: 29,
+                        from: 3,
+                        to: 4
+                    },
+                    PageMove {
+                        page: 13,
+                        from: 1,
+                        to: 4
+                    },
+                ],
+            })
+        );
+        assert_eq!(
+            explain_updates("1|2\n\n1,2\n2,1\n"),
+            Ok("\
+Update 1: 1,2
+  in the right order
+
+Update 2: 2,1
+  broken rule 1|2: 1 at position 2, 2 at position 1
+  move 2 from position 1 to position 2
+  original:  2,1
+  corrected: 1,2
+"
+            .to_string())
+        );
+    }
+
+    fn apply_moves(update: &[i32], moves: &[PageMove]) -> Vec<i32> {
+        let mut pages = update.to_vec();
+
+        for page_move in moves {
+            assert_eq!(pages.remove(page_move.from), page_move.page);
+            pages.insert(page_move.to, page_move.page);
+        }
+
+        pages
+    }
+
+    #[test]
+    fn test_explain_fewest_moves() {
+        let rule_graph = RuleGraph::new(&[(4, 1), (4, 2)]);
+        let explanation = rule_graph.explain(&[1, 2, 3, 4]).unwrap();
+
+        assert_eq!(explanation.corrected, vec![4, 1, 2, 3]);
+        assert_eq!(
+            explanation.moves,
+            vec![PageMove {
+                page: 4,
+                from: 3,
+                to: 0
+            }]
+        );
+
+        // Rules only point from lower to higher pages, so they never form a
+        // cycle. The fewest moves are checked against every set of pages that
+        // could stay in place.
+        let mut random = Xorshift::new(0x2024_1205);
+
+        for _ in 0..300 {
+            let length = 1 + random.below(7) as usize;
+            let rules: Vec<(i32, i32)> = (0..length as i32)
+                .flat_map(|before| ((before + 1)..length as i32).map(move |after| (before, after)))
+                .filter(|_| random.below(3) == 0)
+                .collect();
+            let rule_graph = RuleGraph::new(&rules);
+            let mut update: Vec<i32> = (0..length as i32).collect();
+
+            for index in (1..length).rev() {
+                update.swap(index, random.below(index as u64 + 1) as usize);
+            }
+
+            let explanation = rule_graph.explain(&update).unwrap();
+            let most_in_place = (0..1u32 << length)
+                .filter(|&in_place| {
+                    rule_graph
+                        .order_indices_by(&update, |index, other_index| {
+                            rule_graph.must_precede(update[index], update[other_index])
+                                || (in_place >> index & 1 == 1
+                                    && in_place >> other_index & 1 == 1
+                                    && index < other_index)
+                        })
+                        .is_ok_and(|ordered_indices| ordered_indices.len() == length)
+                })
+                .map(|in_place| in_place.count_ones() as usize)
+                .max()
+                .unwrap_or(0);
+
+            assert!(rule_graph.is_ordered(&explanation.corrected));
+            assert_eq!(
+                apply_moves(&update, &explanation.moves),
+                explanation.corrected
+            );
+            assert_eq!(explanation.moves.len(), length - most_in_place);
+            assert!(explanation
+                .moves
+                .iter()
+                .all(|page_move| page_move.from != page_move.to));
+        }
+    }
+
+    #[test]
+    fn test_to_dot_with_cycle() {
+        assert_eq!(
+            rules_dot("1|2\n2|3\n3|1\n2|4\n3|4\n4|5\n3|5\n1|5\n"),
+            Ok("\
+digraph rules {
+    // Page ordering rules form a cycle: 1 -> 2 -> 3 -> 1
+    1;
+    2;
+    3;
+    4;
+    5;
+    1 -> 2;
+    2 -> 3;
+    2 -> 4;
+    3 -> 1;
+    3 -> 4;
+    4 -> 5;
+}
+"
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let print_queue = PrintQueue::new(TEST_INPUT).unwrap();
+        let rule_graph = RuleGraph::new(&print_queue.rules);
+
+        assert_eq!(
+            rule_graph.to_dot("update", Some(&[61, 13, 29])),
+            "\
+digraph update {
+    13;
+    29;
+    61;
+    29 -> 13 [color=red];
+    61 -> 29;
+}
+"
+        );
+        assert_eq!(
+            rule_graph.to_dot("rules", None),
+            "\
+digraph rules {
+    13;
+    29;
+    47;
+    53;
+    61;
+    75;
+    97;
+    29 -> 13;
+    47 -> 61;
+    53 -> 29;
+    61 -> 53;
+    75 -> 47;
+    97 -> 75;
+}
+"
+        );
+        assert_eq!(
+            rules_dot("1|2\n2|3\n3|1\n1|3\n"),
+            Ok("\
+digraph rules {
+    // Page ordering rules form a cycle: 1 -> 2 -> 3 -> 1
+    1;
+    2;
+    3;
+    1 -> 2;
+    1 -> 3;
+    2 -> 3;
+    3 -> 1;
+}
+"
+            .to_string())
+        );
+    }
+
+    #[test]
+    fn test_order() {
+        let print_queue = PrintQueue::new(TEST_INPUT).unwrap();
+        let rule_graph = RuleGraph::new(&print_queue.rules);
+
+        assert_eq!(
+            rule_graph.order(&[75, 97, 47, 61, 53]),
+            Ok(vec![97, 75, 47, 61, 53])
+        );
+        assert_eq!(rule_graph.order(&[61, 13, 29]), Ok(vec![61, 29, 13]));
+        assert_eq!(
+            rule_graph.order(&[97, 13, 75, 29, 47]),
+            Ok(vec![97, 75, 47, 29, 13])
+        );
+        assert_eq!(
+            rule_graph.order(&[75, 47, 61, 53, 29]),
+            Ok(vec![75, 47, 61, 53, 29])
+        );
+        assert_eq!(
+            part1("1|2\n\n1,x,2\n"),
+            Err("Could not parse page number \"x\" on line 3".to_string())
+        );
+    }
 }
@@ -194,15 +194,15 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Position {
-    x: i32,
-    y: i32,
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Direction {
-    x: i32,
-    y: i32,
+pub struct Direction {
+    pub x: i32,
+    pub y: i32,
 }
 
 impl Direction {
@@ -221,9 +221,69 @@ impl Direction {
     }
 }
 
-struct Map {
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Guard {
+    pub position: Position,
+    pub direction: Direction,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CollisionKind {
+    SameCell,
+    Swap,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Collision {
+    pub tick: usize,
+    pub guards: (usize, usize),
+    pub position: Position,
+    pub kind: CollisionKind,
+}
+
+#[derive(Debug)]
+pub struct Patrol {
+    pub visited_positions: Vec<HashSet<Position>>,
+    pub loop_detected: Vec<bool>,
+    pub collisions: Vec<Collision>,
+}
+
+impl Patrol {
+    pub fn union(&self) -> HashSet<Position> {
+        self.visited_positions
+            .iter()
+            .flat_map(|visited| visited.iter().copied())
+            .collect()
+    }
+
+    pub fn intersection(&self) -> HashSet<Position> {
+        let mut guards_visited = self.visited_positions.iter();
+
+        let mut intersection = match guards_visited.next() {
+            Some(visited) => visited.clone(),
+            None => return HashSet::new(),
+        };
+
+        for visited in guards_visited {
+            intersection.retain(|position| visited.contains(position));
+        }
+
+        intersection
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Obstruction {
+    pub positions: Vec<Position>,
+    pub steps: usize,
+    pub visited_positions: usize,
+    pub loop_detected: bool,
+}
+
+pub struct Map {
     visited_positions: HashSet<Position>,
     obstacles: HashMap<Position, i32>,
+    guards: Vec<Guard>,
     position: Position,
     x_max: i32,
     y_max: i32,
@@ -235,41 +295,50 @@ impl Map {
     pub fn new(input: &str) -> Self {
         let visited_positions: HashSet<Position> = HashSet::new();
         let mut obstacles: HashMap<Position, i32> = HashMap::new();
-        let mut position: Position = Position { x: -1, y: -1 };
-        let y_max = non_empty_lines(input).collect::<Vec<&str>>().len() as i32 - 1;
-        let x_max = non_empty_lines(input).collect::<Vec<&str>>()[0].len() as i32 - 1;
-        let direction = Direction::new(0, -1);
+        let mut guards: Vec<Guard> = Vec::new();
+        let y_max = non_empty_lines(input).count() as i32 - 1;
+        let x_max = non_empty_lines(input).next().map_or(0, |line| line.len()) as i32 - 1;
 
         for (y, line) in non_empty_lines(input).enumerate() {
             for (x, position_item) in line.chars().enumerate() {
+                let position = Position {
+                    x: x as i32,
+                    y: y as i32,
+                };
+
                 match position_item {
                     '#' => {
-                        obstacles.insert(
-                            Position {
-                                x: x as i32,
-                                y: y as i32,
-                            },
-                            0,
-                        );
+                        obstacles.insert(position, 0);
                     }
-                    '^' => {
-                        position = Position {
-                            x: x as i32,
-                            y: y as i32,
+                    '^' | '>' | 'v' | '<' => {
+                        let direction = match position_item {
+                            '^' => Direction::new(0, -1),
+                            '>' => Direction::new(1, 0),
+                            'v' => Direction::new(0, 1),
+                            _ => Direction::new(-1, 0),
                         };
+
+                        guards.push(Guard {
+                            position,
+                            direction,
+                        });
                     }
                     _ => {}
                 }
             }
         }
 
-        if position == (Position { x: -1, y: -1 }) {
-            panic!("No starting position found!");
-        }
+        // Without a guard the single guard walk starts off the map and visits
+        // nothing, and a patrol has no guards to move.
+        let (position, direction) = match guards.first() {
+            Some(guard) => (guard.position, guard.direction),
+            None => (Position { x: -1, y: -1 }, Direction::new(0, -1)),
+        };
 
         Self {
             visited_positions,
             obstacles,
+            guards,
             position,
             x_max,
             y_max,
@@ -278,6 +347,104 @@ impl Map {
         }
     }
 
+    pub fn guards(&self) -> &[Guard] {
+        &self.guards
+    }
+
+    fn is_inside(&self, position: &Position) -> bool {
+        position.x >= 0 && position.x <= self.x_max && position.y >= 0 && position.y <= self.y_max
+    }
+
+    // Moves every guard one step per tick (a turn counts as a step) until each of
+    // them has either left the map or repeated a position and direction, which
+    // means it is stuck in a loop. Guards do not block each other. A looping
+    // guard keeps walking its loop while other guards are still on their way
+    // out, so it can still run into them.
+    pub fn patrol(&self) -> Patrol {
+        let mut guards = self.guards.clone();
+        let mut visited_positions: Vec<HashSet<Position>> = guards
+            .iter()
+            .map(|guard| HashSet::from([guard.position]))
+            .collect();
+        let mut seen_states: Vec<HashSet<Guard>> =
+            guards.iter().map(|guard| HashSet::from([*guard])).collect();
+        let mut on_map: Vec<bool> = guards
+            .iter()
+            .map(|guard| self.is_inside(&guard.position))
+            .collect();
+        let mut loop_detected = vec![false; guards.len()];
+        let mut collisions: Vec<Collision> = Vec::new();
+        let mut tick = 0;
+
+        while (0..guards.len()).any(|index| on_map[index] && !loop_detected[index]) {
+            tick += 1;
+            let previous_positions: Vec<Position> =
+                guards.iter().map(|guard| guard.position).collect();
+            let was_on_map = on_map.clone();
+
+            for (index, guard) in guards.iter_mut().enumerate() {
+                if !on_map[index] {
+                    continue;
+                }
+
+                let next_position = Position {
+                    x: guard.position.x + guard.direction.x,
+                    y: guard.position.y + guard.direction.y,
+                };
+
+                if self.obstacles.contains_key(&next_position) {
+                    guard.direction = guard.direction.turn();
+                } else {
+                    guard.position = next_position;
+                }
+
+                if !self.is_inside(&guard.position) {
+                    on_map[index] = false;
+                } else if loop_detected[index] {
+                    continue;
+                } else if !seen_states[index].insert(*guard) {
+                    loop_detected[index] = true;
+                } else {
+                    visited_positions[index].insert(guard.position);
+                }
+            }
+
+            for first in 0..guards.len() {
+                for second in (first + 1)..guards.len() {
+                    if !was_on_map[first] || !was_on_map[second] {
+                        continue;
+                    }
+
+                    if guards[first].position == guards[second].position
+                        && self.is_inside(&guards[first].position)
+                    {
+                        collisions.push(Collision {
+                            tick,
+                            guards: (first, second),
+                            position: guards[first].position,
+                            kind: CollisionKind::SameCell,
+                        });
+                    } else if guards[first].position == previous_positions[second]
+                        && guards[second].position == previous_positions[first]
+                    {
+                        collisions.push(Collision {
+                            tick,
+                            guards: (first, second),
+                            position: guards[first].position,
+                            kind: CollisionKind::Swap,
+                        });
+                    }
+                }
+            }
+        }
+
+        Patrol {
+            visited_positions,
+            loop_detected,
+            collisions,
+        }
+    }
+
     pub fn visited_positions(&mut self) -> &HashSet<Position> {
         while self.position.x >= 0
             && self.position.x <= self.x_max
@@ -315,7 +482,11 @@ impl Map {
     }
 
     pub fn add_obstacle(&mut self, x: i32, y: i32) -> bool {
-        if self.position == (Position { x, y }) {
+        if self
+            .guards
+            .iter()
+            .any(|guard| guard.position == (Position { x, y }))
+        {
             return false;
         }
 
@@ -329,6 +500,188 @@ impl Map {
             false
         }
     }
+
+    // Walks the first guard with the given obstructions added, stopping when it
+    // leaves the map or repeats a position and direction as in `patrol`. The
+    // obstruction analysis only follows the first guard, and other guards do not
+    // block it.
+    fn walk_with_obstacles(&self, positions: &[Position]) -> Option<(Obstruction, Vec<Position>)> {
+        let mut guard = *self.guards.first()?;
+        let added_obstacles: HashSet<Position> = positions.iter().copied().collect();
+
+        if added_obstacles.len() < positions.len()
+            || positions.iter().any(|position| {
+                self.obstacles.contains_key(position)
+                    || self.guards.iter().any(|guard| guard.position == *position)
+            })
+        {
+            return None;
+        }
+
+        let mut visited_positions: HashSet<Position> = HashSet::new();
+        let mut seen_states: HashSet<Guard> = HashSet::from([guard]);
+        let mut steps = 0;
+        let mut loop_detected = false;
+
+        while self.is_inside(&guard.position) {
+            visited_positions.insert(guard.position);
+
+            let next_position = Position {
+                x: guard.position.x + guard.direction.x,
+                y: guard.position.y + guard.direction.y,
+            };
+
+            steps += 1;
+
+            if self.obstacles.contains_key(&next_position)
+                || added_obstacles.contains(&next_position)
+            {
+                guard.direction = guard.direction.turn();
+            } else {
+                guard.position = next_position;
+            }
+
+            if !seen_states.insert(guard) {
+                loop_detected = true;
+                break;
+            }
+        }
+
+        let mut path: Vec<Position> = visited_positions.into_iter().collect();
+        path.sort_by_key(|position| (position.y, position.x));
+
+        Some((
+            Obstruction {
+                positions: positions.to_vec(),
+                steps,
+                visited_positions: path.len(),
+                loop_detected,
+            },
+            path,
+        ))
+    }
+
+    // Only cells on the guard's current path can change where it goes, so those
+    // are the only candidates tried for a single added obstruction.
+    pub fn obstruction_candidates(&self) -> Vec<Obstruction> {
+        let (_, path) = match self.walk_with_obstacles(&[]) {
+            Some(walk) => walk,
+            None => return Vec::new(),
+        };
+
+        path.iter()
+            .filter_map(|position| self.walk_with_obstacles(&[*position]))
+            .map(|(obstruction, _)| obstruction)
+            .collect()
+    }
+
+    pub fn fastest_exit_obstructions(&self) -> Vec<Obstruction> {
+        let mut candidates: Vec<Obstruction> = self
+            .obstruction_candidates()
+            .into_iter()
+            .filter(|obstruction| !obstruction.loop_detected)
+            .collect();
+
+        candidates.sort_by_key(|obstruction| obstruction.steps);
+
+        candidates
+    }
+
+    pub fn widest_coverage_obstructions(&self) -> Vec<Obstruction> {
+        let mut candidates: Vec<Obstruction> = self
+            .obstruction_candidates()
+            .into_iter()
+            .filter(|obstruction| !obstruction.loop_detected)
+            .collect();
+
+        candidates.sort_by_key(|obstruction| std::cmp::Reverse(obstruction.visited_positions));
+
+        candidates
+    }
+
+    // Returns every set of at most `k` added obstructions that traps the guard in
+    // a loop, leaving out sets that contain a smaller trapping set. Sets are
+    // ranked by size and then by how quickly the loop is detected.
+    pub fn looping_obstruction_sets(&self, k: usize) -> Vec<Obstruction> {
+        let mut found: HashMap<Vec<Position>, Obstruction> = HashMap::new();
+
+        self.search_looping_obstruction_sets(&mut Vec::new(), k, &mut HashSet::new(), &mut found);
+
+        let mut sets: Vec<Obstruction> = found.into_values().collect();
+        sets.sort_by_key(|obstruction| {
+            (
+                obstruction.positions.len(),
+                obstruction.steps,
+                obstruction
+                    .positions
+                    .iter()
+                    .map(|position| (position.y, position.x))
+                    .collect::<Vec<(i32, i32)>>(),
+            )
+        });
+
+        let mut minimal_sets: Vec<Obstruction> = Vec::new();
+
+        for set in sets {
+            if !minimal_sets.iter().any(|smaller| {
+                smaller
+                    .positions
+                    .iter()
+                    .all(|position| set.positions.contains(position))
+            }) {
+                minimal_sets.push(set);
+            }
+        }
+
+        minimal_sets
+    }
+
+    // Each set of placements is walked once, whatever order it was reached in.
+    fn search_looping_obstruction_sets(
+        &self,
+        placed: &mut Vec<Position>,
+        k: usize,
+        explored: &mut HashSet<Vec<Position>>,
+        found: &mut HashMap<Vec<Position>, Obstruction>,
+    ) {
+        let mut key = placed.clone();
+        key.sort_by_key(|position| (position.y, position.x));
+
+        if !explored.insert(key.clone()) {
+            return;
+        }
+
+        let (obstruction, path) = match self.walk_with_obstacles(placed) {
+            Some(walk) => walk,
+            None => return,
+        };
+
+        if obstruction.loop_detected {
+            found.insert(
+                key.clone(),
+                Obstruction {
+                    positions: key,
+                    ..obstruction
+                },
+            );
+
+            return;
+        }
+
+        if placed.len() == k {
+            return;
+        }
+
+        for position in path {
+            if placed.contains(&position) {
+                continue;
+            }
+
+            placed.push(position);
+            self.search_looping_obstruction_sets(placed, k, explored, found);
+            placed.pop();
+        }
+    }
 }
 
 fn non_empty_lines(input: &str) -> impl Iterator<Item = &str> {
@@ -390,4 +743,122 @@ mod tests {
     fn test_part2() {
         assert_eq!(part2(TEST_INPUT), Ok(6.to_string()));
     }
+
+    #[test]
+    fn test_patrol_single_guard() {
+        let patrol = Map::new(TEST_INPUT).patrol();
+
+        assert_eq!(patrol.visited_positions.len(), 1);
+        assert_eq!(patrol.union().len(), 41);
+        assert_eq!(patrol.intersection().len(), 41);
+        assert_eq!(patrol.loop_detected, vec![false]);
+        assert!(patrol.collisions.is_empty());
+    }
+
+    #[test]
+    fn test_patrol_multiple_guards() {
+        let map = Map::new(">.<\n...\n");
+        let patrol = map.patrol();
+
+        assert_eq!(map.guards().len(), 2);
+        assert_eq!(patrol.union().len(), 3);
+        assert_eq!(patrol.intersection().len(), 3);
+        assert_eq!(
+            patrol.collisions,
+            vec![Collision {
+                tick: 1,
+                guards: (0, 1),
+                position: Position { x: 1, y: 0 },
+                kind: CollisionKind::SameCell,
+            }]
+        );
+
+        let patrol = Map::new(".><.\n").patrol();
+
+        assert_eq!(patrol.union().len(), 4);
+        assert_eq!(patrol.intersection().len(), 2);
+        assert_eq!(patrol.collisions.len(), 1);
+        assert_eq!(patrol.collisions[0].kind, CollisionKind::Swap);
+
+        let mut rows: Vec<Vec<char>> = vec![vec!['.'; 6]; 25];
+        for (x, y) in [(1, 20), (5, 21), (0, 22), (4, 23)] {
+            rows[y][x] = '#';
+        }
+        rows[22][1] = '^';
+        rows[5][3] = 'v';
+        let looping_input: String = rows
+            .iter()
+            .map(|row| row.iter().collect::<String>() + "\n")
+            .collect();
+        let patrol = Map::new(&looping_input).patrol();
+
+        assert_eq!(patrol.loop_detected, vec![false, true]);
+        assert!(patrol.collisions.contains(&Collision {
+            tick: 16,
+            guards: (0, 1),
+            position: Position { x: 3, y: 21 },
+            kind: CollisionKind::SameCell,
+        }));
+    }
+
+    #[test]
+    fn test_map_without_guards() {
+        let map = Map::new("..#\n...\n");
+        let patrol = map.patrol();
+
+        assert!(map.guards().is_empty());
+        assert!(patrol.visited_positions.is_empty());
+        assert!(patrol.collisions.is_empty());
+        assert_eq!(part1("..#\n...\n"), Ok(0.to_string()));
+    }
+
+    #[test]
+    fn test_obstruction_analysis() {
+        let map = Map::new(TEST_INPUT);
+
+        let fastest_exit = map.fastest_exit_obstructions();
+        assert_eq!(fastest_exit.len(), 34);
+        assert!(fastest_exit
+            .windows(2)
+            .all(|pair| pair[0].steps <= pair[1].steps));
+
+        let widest_coverage = map.widest_coverage_obstructions();
+        assert_eq!(widest_coverage.len(), 34);
+        assert!(widest_coverage
+            .windows(2)
+            .all(|pair| pair[0].visited_positions >= pair[1].visited_positions));
+
+        let looping_sets = map.looping_obstruction_sets(1);
+        assert_eq!(looping_sets.len(), 6);
+        assert!(looping_sets
+            .iter()
+            .any(|set| set.positions == vec![Position { x: 3, y: 6 }]));
+    }
+
+    #[test]
+    fn test_obstruction_analysis_loop_check() {
+        // The guard runs into the obstacle at (5, 5) from three sides and then
+        // leaves the map through the top.
+        let map = Map::new(
+            "\
+..........
+..........
+.#........
+......#...
+...#......
+.....#....
+#.......#.
+....#.....
+..#..^....
+.......#..
+",
+        );
+
+        assert_eq!(map.patrol().loop_detected, vec![false]);
+        assert!(map.looping_obstruction_sets(0).is_empty());
+        assert!(map
+            .fastest_exit_obstructions()
+            .iter()
+            .all(|obstruction| !obstruction.loop_detected));
+    }
 }
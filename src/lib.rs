@@ -4,3 +4,29 @@ pub mod day3;
 pub mod day4;
 pub mod day5;
 pub mod day6;
+
+#[cfg(test)]
+mod test_utils {
+    // Xorshift generator so that randomised tests are repeatable without an
+    // extra dependency.
+    pub struct Xorshift {
+        seed: u64,
+    }
+
+    impl Xorshift {
+        pub fn new(seed: u64) -> Self {
+            Self { seed }
+        }
+
+        pub fn next_u64(&mut self) -> u64 {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 7;
+            self.seed ^= self.seed << 17;
+            self.seed
+        }
+
+        pub fn below(&mut self, bound: u64) -> u64 {
+            self.next_u64() % bound
+        }
+    }
+}
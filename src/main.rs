@@ -1,17 +1,11 @@
+use aoc_rust_2024::{day1, day2, day3, day4, day5, day6};
 use clap::Parser;
 use env_logger::Builder;
-use log::LevelFilter;
+use log::{info, LevelFilter};
 use std::fs;
 use std::path::PathBuf;
 use std::time::Instant;
 
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-
 #[derive(Parser)]
 #[command(name = "advent-of-code-2024")]
 #[command(about = "Calculate solutions for Advent of Code 2024 using Rust and the provided input", long_about = None)]
@@ -28,6 +22,8 @@ struct Cli {
     #[arg(long, short, action)]
     // debug: bool
     debug: bool,
+    #[arg(long)]
+    report: Option<String>,
 }
 
 fn load_file(filename: PathBuf) -> Option<std::string::String> {
@@ -62,6 +58,24 @@ fn solve(day: u8, part: u8) -> Result<SolverFn, (u8, u8)> {
     }
 }
 
+fn report(day: u8, part: u8, name: &str) -> Result<SolverFn, (u8, u8)> {
+    match (day, part, name) {
+        (1, _, "reconcile") => Ok(day1::reconciliation_table),
+        (1, 2, "breakdown") => Ok(day1::similarity_breakdown_table),
+        (1, 2, "breakdown-csv") => Ok(day1::similarity_breakdown_csv),
+        (2, 1, "table") => Ok(day2::part1_table),
+        (2, 2, "table") => Ok(day2::part2_table),
+        (2, _, "analytics") => Ok(day2::analytics_table),
+        (2, _, "analytics-json") => Ok(day2::analytics_json),
+        (3, 1, "disasm") => Ok(day3::part1_disasm),
+        (3, 2, "disasm") => Ok(day3::part2_disasm),
+        (5, _, "explain") => Ok(day5::explain_updates),
+        (5, _, "dot") => Ok(day5::rules_dot),
+        (5, _, "dot-updates") => Ok(day5::updates_dot),
+        (_, _, _) => Err((day, part)),
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
     let chrono_start;
@@ -80,7 +94,12 @@ fn main() {
         .collect();
 
     if let Some(puzzle_input) = load_file(day_input) {
-        match solve(cli.day, cli.part) {
+        let solver = match &cli.report {
+            Some(name) => report(cli.day, cli.part, name),
+            None => solve(cli.day, cli.part),
+        };
+
+        match solver {
             Ok(solve_function) => {
                 chrono_start = Instant::now();
                 solution_result = solve_function(&puzzle_input);
@@ -88,6 +107,12 @@ fn main() {
                 total_time += chrono_stop;
 
                 match solution_result {
+                    // Reports keep stdout to themselves so they can be piped into other tools.
+                    Ok(solution) if cli.report.is_some() => {
+                        print!("{}", solution);
+                        info!("Time: {}μs", chrono_stop);
+                        return;
+                    }
                     Ok(solution) => println!(
                         "Solution of Day {}, Part {}: {}, Time: {}μs",
                         cli.day, cli.part, solution, chrono_stop
@@ -99,7 +124,13 @@ fn main() {
                 }
                 println!("\nTotal Time: {}μs", total_time);
             }
-            Err(_) => println!("Unsupported day {} and part {}", cli.day, cli.part),
+            Err(_) => match &cli.report {
+                Some(name) => println!(
+                    "Unsupported report {} for day {} and part {}",
+                    name, cli.day, cli.part
+                ),
+                None => println!("Unsupported day {} and part {}", cli.day, cli.part),
+            },
         }
     }
 }